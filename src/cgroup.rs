@@ -0,0 +1,414 @@
+//! Manage control groups of the cgroup v2 unified hierarchy
+//!
+//! A [`Cgroup`](struct.Cgroup.html) is an open directory of the hierarchy,
+//! usually mounted on `/sys/fs/cgroup`.  Interface files are accessed
+//! relative to it, so a `Cgroup` keeps working if the hierarchy is moved or
+//! seen through another mount.  Its file descriptor is also the one expected
+//! by [`CloneArgs::into_cgroup`](../sched/struct.CloneArgs.html#method.into_cgroup).
+//!
+//! See [`cgroups(7)`](https://man7.org/linux/man-pages/man7/cgroups.7.html)
+//! and the kernel's `Documentation/admin-guide/cgroup-v2.rst` for the
+//! meaning of each file.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use nix::cgroup::{Cgroup, Limit};
+//! use nix::sys::stat::Mode;
+//! use nix::unistd::getpid;
+//!
+//! let root = Cgroup::root().unwrap();
+//! let job = root.create_child("job", Mode::from_bits_truncate(0o755)).unwrap();
+//! job.set_memory_max(Limit::Value(512 << 20)).unwrap();
+//! job.set_pids_max(Limit::Value(64)).unwrap();
+//! job.add_process(getpid()).unwrap();
+//! ```
+use std::fmt;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::mem;
+use std::str::FromStr;
+use crate::{Error, NixPath, Result};
+use crate::errno::Errno;
+use crate::fcntl::{self, OFlag};
+use crate::sys::stat::{mkdirat, Mode};
+use crate::unistd::{self, Pid, UnlinkatFlags};
+
+/// Where the unified hierarchy is usually mounted.
+pub const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// An open control group directory.
+///
+/// This type implements `Drop`, so the file descriptor is closed when it
+/// goes out of scope.
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct Cgroup(RawFd);
+
+impl Cgroup {
+    /// Open the root of the hierarchy mounted on
+    /// [`CGROUP_ROOT`](constant.CGROUP_ROOT.html).
+    pub fn root() -> Result<Cgroup> {
+        Cgroup::open(CGROUP_ROOT)
+    }
+
+    /// Open the control group directory at `path`.
+    pub fn open<P: ?Sized + NixPath>(path: &P) -> Result<Cgroup> {
+        Cgroup::open_at(libc::AT_FDCWD, path)
+    }
+
+    /// Open the control group directory at `path`, relative to `dirfd`.
+    pub fn open_at<P: ?Sized + NixPath>(dirfd: RawFd, path: &P) -> Result<Cgroup> {
+        let oflag = OFlag::O_RDONLY | OFlag::O_DIRECTORY | OFlag::O_CLOEXEC;
+        fcntl::openat(dirfd, path, oflag, Mode::empty()).map(Cgroup)
+    }
+
+    /// Open the child group `name`.
+    pub fn open_child<P: ?Sized + NixPath>(&self, name: &P) -> Result<Cgroup> {
+        Cgroup::open_at(self.0, name)
+    }
+
+    /// Create the child group `name` and open it.
+    pub fn create_child<P: ?Sized + NixPath>(&self, name: &P, mode: Mode)
+        -> Result<Cgroup>
+    {
+        mkdirat(self.0, name, mode)?;
+        self.open_child(name)
+    }
+
+    /// Remove the child group `name`, which must have no processes and no
+    /// children of its own.
+    pub fn remove_child<P: ?Sized + NixPath>(&self, name: &P) -> Result<()> {
+        unistd::unlinkat(Some(self.0), name, UnlinkatFlags::RemoveDir)
+    }
+
+    /// Read the interface file `name` of the group.
+    pub fn read<P: ?Sized + NixPath>(&self, name: &P) -> Result<String> {
+        let fd = fcntl::openat(self.0, name, OFlag::O_RDONLY | OFlag::O_CLOEXEC,
+                               Mode::empty())?;
+        let res = unistd::read_to_end(fd);
+        unistd::close(fd)?;
+        String::from_utf8(res?).map_err(|_| Errno::EINVAL)
+    }
+
+    /// Write `value` to the interface file `name` of the group.
+    ///
+    /// The kernel parses each `write(2)` on its own, so the value is written
+    /// at once.
+    pub fn write<P: ?Sized + NixPath>(&self, name: &P, value: &str) -> Result<()> {
+        let oflag = OFlag::O_WRONLY | OFlag::O_TRUNC | OFlag::O_CLOEXEC;
+        let fd = fcntl::openat(self.0, name, oflag, Mode::empty())?;
+        let res = unistd::write(fd, value.as_bytes()).and_then(|n| {
+            if n == value.len() { Ok(()) } else { Err(Errno::EIO) }
+        });
+        unistd::close(fd)?;
+        res
+    }
+
+    /// Read an interface file holding a single value, like `pids.max`.
+    pub fn read_value<P, T>(&self, name: &P) -> Result<T>
+        where P: ?Sized + NixPath, T: FromStr
+    {
+        self.read(name)?.trim().parse().map_err(|_| Errno::EINVAL)
+    }
+
+    /// Write an interface file holding a single value, like `pids.max`.
+    pub fn write_value<P, T>(&self, name: &P, value: T) -> Result<()>
+        where P: ?Sized + NixPath, T: fmt::Display
+    {
+        self.write(name, &value.to_string())
+    }
+
+    /// Move the process `pid`, with all of its threads, into the group.
+    pub fn add_process(&self, pid: Pid) -> Result<()> {
+        self.write_value("cgroup.procs", pid)
+    }
+
+    /// Move the thread `tid` into the group, which must be threaded.
+    pub fn add_thread(&self, tid: Pid) -> Result<()> {
+        self.write_value("cgroup.threads", tid)
+    }
+
+    /// The processes in the group.
+    pub fn processes(&self) -> Result<Vec<Pid>> {
+        self.read_pids("cgroup.procs")
+    }
+
+    /// The threads in the group.
+    pub fn threads(&self) -> Result<Vec<Pid>> {
+        self.read_pids("cgroup.threads")
+    }
+
+    fn read_pids(&self, name: &str) -> Result<Vec<Pid>> {
+        self.read(name)?
+            .lines()
+            .map(|line| line.parse().map(Pid::from_raw).map_err(|_| Errno::EINVAL))
+            .collect()
+    }
+
+    /// The controllers available to the group, from `cgroup.controllers`.
+    pub fn controllers(&self) -> Result<Vec<String>> {
+        Ok(self.read("cgroup.controllers")?
+            .split_whitespace()
+            .map(String::from)
+            .collect())
+    }
+
+    /// The controllers enabled for the children of the group, from
+    /// `cgroup.subtree_control`.
+    pub fn subtree_control(&self) -> Result<Vec<String>> {
+        Ok(self.read("cgroup.subtree_control")?
+            .split_whitespace()
+            .map(String::from)
+            .collect())
+    }
+
+    /// Enable and disable controllers for the children of the group, by
+    /// writing `cgroup.subtree_control`.
+    pub fn set_subtree_control(&self, enable: &[&str], disable: &[&str]) -> Result<()> {
+        let changes = enable.iter().map(|c| format!("+{}", c))
+            .chain(disable.iter().map(|c| format!("-{}", c)))
+            .collect::<Vec<_>>();
+        self.write("cgroup.subtree_control", &changes.join(" "))
+    }
+
+    /// The hard memory limit of the group, from `memory.max`.
+    pub fn memory_max(&self) -> Result<Limit> {
+        self.read_value("memory.max")
+    }
+
+    /// Set the hard memory limit of the group, in bytes.
+    pub fn set_memory_max(&self, limit: Limit) -> Result<()> {
+        self.write_value("memory.max", limit)
+    }
+
+    /// The memory usage above which the group is throttled, from
+    /// `memory.high`.
+    pub fn memory_high(&self) -> Result<Limit> {
+        self.read_value("memory.high")
+    }
+
+    /// Set the memory usage above which the group is throttled, in bytes.
+    pub fn set_memory_high(&self, limit: Limit) -> Result<()> {
+        self.write_value("memory.high", limit)
+    }
+
+    /// The current memory usage of the group and its children, in bytes.
+    pub fn memory_current(&self) -> Result<u64> {
+        self.read_value("memory.current")
+    }
+
+    /// Event counters of the memory controller, from `memory.events`.
+    pub fn memory_events(&self) -> Result<MemoryEvents> {
+        self.read("memory.events")?.parse()
+    }
+
+    /// The maximum number of processes in the group, from `pids.max`.
+    pub fn pids_max(&self) -> Result<Limit> {
+        self.read_value("pids.max")
+    }
+
+    /// Set the maximum number of processes in the group.
+    pub fn set_pids_max(&self, limit: Limit) -> Result<()> {
+        self.write_value("pids.max", limit)
+    }
+
+    /// The current number of processes in the group and its children.
+    pub fn pids_current(&self) -> Result<u64> {
+        self.read_value("pids.current")
+    }
+
+    /// The CPU bandwidth limit of the group, from `cpu.max`.
+    pub fn cpu_max(&self) -> Result<CpuMax> {
+        self.read_value("cpu.max")
+    }
+
+    /// Set the CPU bandwidth limit of the group.
+    pub fn set_cpu_max(&self, max: CpuMax) -> Result<()> {
+        self.write_value("cpu.max", max)
+    }
+
+    /// CPU usage statistics of the group, from `cpu.stat`.
+    pub fn cpu_stat(&self) -> Result<CpuStat> {
+        self.read("cpu.stat")?.parse()
+    }
+}
+
+impl AsRawFd for Cgroup {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+impl FromRawFd for Cgroup {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Cgroup(fd)
+    }
+}
+
+impl IntoRawFd for Cgroup {
+    fn into_raw_fd(self) -> RawFd {
+        let fd = self.0;
+        mem::forget(self);
+        fd
+    }
+}
+
+impl Drop for Cgroup {
+    fn drop(&mut self) {
+        // The cgroup itself is left in place.  A directory descriptor has
+        // nothing to flush, so EBADF, from a double close, is the only error.
+        let e = unistd::close(self.0);
+        if e == Err(Errno::EBADF) {
+            panic!("Closing an invalid file descriptor!");
+        };
+    }
+}
+
+/// A limit that may be disabled, written as `max` in interface files like
+/// `memory.max` and `pids.max`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Limit {
+    /// No limit.
+    Max,
+    /// Limit to the given amount.
+    Value(u64),
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Limit::Max => f.write_str("max"),
+            Limit::Value(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl FromStr for Limit {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Limit> {
+        match s.trim() {
+            "max" => Ok(Limit::Max),
+            value => value.parse().map(Limit::Value).map_err(|_| Errno::EINVAL),
+        }
+    }
+}
+
+/// CPU bandwidth limit, as in `cpu.max`: the group may run for `quota`
+/// microseconds in each `period` microseconds.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct CpuMax {
+    /// Allowed run time per period, in microseconds.
+    pub quota: Limit,
+    /// Length of the period, in microseconds.
+    pub period: u64,
+}
+
+impl fmt::Display for CpuMax {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.quota, self.period)
+    }
+}
+
+impl FromStr for CpuMax {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<CpuMax> {
+        let mut fields = s.split_whitespace();
+        let quota = fields.next().ok_or(Errno::EINVAL)?.parse()?;
+        let period = fields.next().ok_or(Errno::EINVAL)?
+            .parse().map_err(|_| Errno::EINVAL)?;
+        if fields.next().is_some() {
+            return Err(Errno::EINVAL);
+        }
+        Ok(CpuMax { quota, period })
+    }
+}
+
+// Parse a flat keyed file, made of "<key> <value>" lines, calling `set` for
+// each of them.  Unknown keys are left for `set` to ignore, as newer kernels
+// add some.
+fn parse_flat_keyed<F>(s: &str, mut set: F) -> Result<()>
+    where F: FnMut(&str, u64)
+{
+    for line in s.lines() {
+        let mut fields = line.split_whitespace();
+        match (fields.next(), fields.next(), fields.next()) {
+            (Some(key), Some(value), None) => {
+                set(key, value.parse().map_err(|_| Errno::EINVAL)?)
+            },
+            _ => return Err(Errno::EINVAL),
+        }
+    }
+    Ok(())
+}
+
+/// Event counters of the memory controller, as in `memory.events`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct MemoryEvents {
+    /// Times the usage was below `memory.low` but memory was reclaimed
+    /// anyway.
+    pub low: u64,
+    /// Times the group was throttled for going over `memory.high`.
+    pub high: u64,
+    /// Times the usage was about to go over `memory.max`.
+    pub max: u64,
+    /// Times the group ran out of memory.
+    pub oom: u64,
+    /// Processes of the group killed by the OOM killer.
+    pub oom_kill: u64,
+}
+
+impl FromStr for MemoryEvents {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<MemoryEvents> {
+        let mut events = MemoryEvents::default();
+        parse_flat_keyed(s, |key, value| match key {
+            "low" => events.low = value,
+            "high" => events.high = value,
+            "max" => events.max = value,
+            "oom" => events.oom = value,
+            "oom_kill" => events.oom_kill = value,
+            _ => (),
+        })?;
+        Ok(events)
+    }
+}
+
+/// CPU usage statistics, as in `cpu.stat`.
+///
+/// The throttling statistics are only reported when the `cpu` controller is
+/// enabled, and are zero otherwise.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct CpuStat {
+    /// Total CPU time, in microseconds.
+    pub usage_usec: u64,
+    /// CPU time spent in user mode, in microseconds.
+    pub user_usec: u64,
+    /// CPU time spent in kernel mode, in microseconds.
+    pub system_usec: u64,
+    /// Number of elapsed `cpu.max` periods.
+    pub nr_periods: u64,
+    /// Number of periods during which the group was throttled.
+    pub nr_throttled: u64,
+    /// Total time the group was throttled, in microseconds.
+    pub throttled_usec: u64,
+}
+
+impl FromStr for CpuStat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<CpuStat> {
+        let mut stat = CpuStat::default();
+        parse_flat_keyed(s, |key, value| match key {
+            "usage_usec" => stat.usage_usec = value,
+            "user_usec" => stat.user_usec = value,
+            "system_usec" => stat.system_usec = value,
+            "nr_periods" => stat.nr_periods = value,
+            "nr_throttled" => stat.nr_throttled = value,
+            "throttled_usec" => stat.throttled_usec = value,
+            _ => (),
+        })?;
+        Ok(stat)
+    }
+}
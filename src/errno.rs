@@ -41,6 +41,15 @@ fn clear() {
     }
 }
 
+/// Sets the platform-specific errno, for signal handlers that must preserve it
+#[cfg(not(target_os = "redox"))]
+pub(crate) fn set_errno(errno: i32) {
+    // Safe because errno is a thread-local variable
+    unsafe {
+        *errno_location() = errno as c_int;
+    }
+}
+
 /// Returns the platform-specific value of errno
 pub fn errno() -> i32 {
     unsafe {
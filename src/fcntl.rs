@@ -25,17 +25,21 @@ use crate::sys::uio::IoVec; // For vmsplice
 pub use self::posix_fadvise::*;
 
 #[cfg(not(target_os = "redox"))]
-libc_bitflags! {
+bitflags::bitflags! {
     pub struct AtFlags: c_int {
-        AT_REMOVEDIR;
-        AT_SYMLINK_FOLLOW;
-        AT_SYMLINK_NOFOLLOW;
+        const AT_REMOVEDIR = libc::AT_REMOVEDIR;
+        const AT_SYMLINK_FOLLOW = libc::AT_SYMLINK_FOLLOW;
+        const AT_SYMLINK_NOFOLLOW = libc::AT_SYMLINK_NOFOLLOW;
         #[cfg(any(target_os = "android", target_os = "linux"))]
-        AT_NO_AUTOMOUNT;
+        const AT_NO_AUTOMOUNT = libc::AT_NO_AUTOMOUNT;
         #[cfg(any(target_os = "android", target_os = "linux"))]
-        AT_EMPTY_PATH;
+        const AT_EMPTY_PATH = libc::AT_EMPTY_PATH;
         #[cfg(any(target_os = "illumos", target_os = "solaris"))]
-        AT_EACCESS;
+        const AT_EACCESS = libc::AT_EACCESS;
+        /// Apply to the whole mount tree.  Used by `mount_setattr`.
+        // libc does not export this yet
+        #[cfg(target_os = "linux")]
+        const AT_RECURSIVE = 0x8000;
     }
 }
 
@@ -4,10 +4,14 @@
 //! of interfaces and their associated addresses.
 
 use cfg_if::cfg_if;
+use libc::c_uint;
+use std::collections::BTreeMap;
 use std::ffi;
 use std::iter::Iterator;
 use std::mem;
 use std::option::Option;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use std::ptr;
 
 use crate::{Result, Errno};
 use crate::sys::socket::SockAddr;
@@ -28,6 +32,130 @@ pub struct InterfaceAddress {
     pub broadcast: Option<SockAddr>,
     /// Point-to-point destination address
     pub destination: Option<SockAddr>,
+    /// Index of the network interface, if it could be resolved with
+    /// `if_nametoindex`
+    pub index: Option<c_uint>,
+    /// Link statistics of the interface.  Only set for the entry whose
+    /// address is of the `AF_PACKET` family.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub stats: Option<LinkStats>,
+}
+
+/// Statistics of a network interface, as reported by the kernel in
+/// `struct rtnl_link_stats`.
+///
+/// These are the same counters found in `/proc/net/dev`.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct LinkStats {
+    /// Total packets received
+    pub rx_packets: u32,
+    /// Total packets transmitted
+    pub tx_packets: u32,
+    /// Total bytes received
+    pub rx_bytes: u32,
+    /// Total bytes transmitted
+    pub tx_bytes: u32,
+    /// Bad packets received
+    pub rx_errors: u32,
+    /// Packet transmit problems
+    pub tx_errors: u32,
+    /// Received packets dropped, for example for lack of buffer space
+    pub rx_dropped: u32,
+    /// Packets dropped before transmission
+    pub tx_dropped: u32,
+    /// Multicast packets received
+    pub multicast: u32,
+    /// Collisions while transmitting
+    pub collisions: u32,
+    /// Received packets with a bad length
+    pub rx_length_errors: u32,
+    /// Receiver ring buffer overflows
+    pub rx_over_errors: u32,
+    /// Received packets with a CRC error
+    pub rx_crc_errors: u32,
+    /// Received frames with an alignment error
+    pub rx_frame_errors: u32,
+    /// Receiver FIFO overruns
+    pub rx_fifo_errors: u32,
+    /// Packets missed by the receiver
+    pub rx_missed_errors: u32,
+    /// Aborted transmissions
+    pub tx_aborted_errors: u32,
+    /// Transmissions that lost the carrier
+    pub tx_carrier_errors: u32,
+    /// Transmitter FIFO underruns
+    pub tx_fifo_errors: u32,
+    /// Heartbeat errors while transmitting
+    pub tx_heartbeat_errors: u32,
+    /// Late collisions while transmitting
+    pub tx_window_errors: u32,
+    /// Compressed packets received
+    pub rx_compressed: u32,
+    /// Compressed packets transmitted
+    pub tx_compressed: u32,
+}
+
+/// The leading fields of `struct rtnl_link_stats` from linux/if_link.h, which
+/// libc does not define.  Newer kernels append more fields.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct rtnl_link_stats {
+    rx_packets: u32,
+    tx_packets: u32,
+    rx_bytes: u32,
+    tx_bytes: u32,
+    rx_errors: u32,
+    tx_errors: u32,
+    rx_dropped: u32,
+    tx_dropped: u32,
+    multicast: u32,
+    collisions: u32,
+    rx_length_errors: u32,
+    rx_over_errors: u32,
+    rx_crc_errors: u32,
+    rx_frame_errors: u32,
+    rx_fifo_errors: u32,
+    rx_missed_errors: u32,
+    tx_aborted_errors: u32,
+    tx_carrier_errors: u32,
+    tx_fifo_errors: u32,
+    tx_heartbeat_errors: u32,
+    tx_window_errors: u32,
+    rx_compressed: u32,
+    tx_compressed: u32,
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl From<rtnl_link_stats> for LinkStats {
+    fn from(s: rtnl_link_stats) -> Self {
+        LinkStats {
+            rx_packets: s.rx_packets,
+            tx_packets: s.tx_packets,
+            rx_bytes: s.rx_bytes,
+            tx_bytes: s.tx_bytes,
+            rx_errors: s.rx_errors,
+            tx_errors: s.tx_errors,
+            rx_dropped: s.rx_dropped,
+            tx_dropped: s.tx_dropped,
+            multicast: s.multicast,
+            collisions: s.collisions,
+            rx_length_errors: s.rx_length_errors,
+            rx_over_errors: s.rx_over_errors,
+            rx_crc_errors: s.rx_crc_errors,
+            rx_frame_errors: s.rx_frame_errors,
+            rx_fifo_errors: s.rx_fifo_errors,
+            rx_missed_errors: s.rx_missed_errors,
+            tx_aborted_errors: s.tx_aborted_errors,
+            tx_carrier_errors: s.tx_carrier_errors,
+            tx_fifo_errors: s.tx_fifo_errors,
+            tx_heartbeat_errors: s.tx_heartbeat_errors,
+            tx_window_errors: s.tx_window_errors,
+            rx_compressed: s.rx_compressed,
+            tx_compressed: s.tx_compressed,
+        }
+    }
 }
 
 cfg_if! {
@@ -55,8 +183,25 @@ impl InterfaceAddress {
             netmask,
             broadcast: None,
             destination: None,
+            index: if_nametoindex(ifname).ok(),
+            #[cfg(any(target_os = "android", target_os = "linux"))]
+            stats: None,
         };
 
+        // For AF_PACKET entries, the C library stores the link statistics it
+        // got from the kernel in ifa_data.
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        {
+            let is_packet = unsafe { info.ifa_addr.as_ref() }
+                .map(|sa| i32::from(sa.sa_family)) == Some(libc::AF_PACKET);
+            if is_packet && !info.ifa_data.is_null() {
+                let stats = unsafe {
+                    ptr::read_unaligned(info.ifa_data as *const rtnl_link_stats)
+                };
+                addr.stats = Some(stats.into());
+            }
+        }
+
         let ifu = get_ifu_from_sockaddr(info);
         if addr.flags.contains(InterfaceFlags::IFF_POINTOPOINT) {
             addr.destination = unsafe { SockAddr::from_libc_sockaddr(ifu) };
@@ -85,6 +230,30 @@ impl Drop for InterfaceAddressIterator {
     }
 }
 
+impl InterfaceAddressIterator {
+    /// Collects the remaining addresses, grouped by interface name.
+    ///
+    /// Within each interface, the addresses keep the order in which
+    /// `getifaddrs` returned them.
+    ///
+    /// # Example
+    /// ```
+    /// let interfaces = nix::ifaddrs::getifaddrs().unwrap().by_interface();
+    /// for (name, addrs) in &interfaces {
+    ///     println!("interface {} has {} addresses", name, addrs.len());
+    /// }
+    /// ```
+    pub fn by_interface(self) -> BTreeMap<String, Vec<InterfaceAddress>> {
+        let mut interfaces = BTreeMap::<String, Vec<InterfaceAddress>>::new();
+        for addr in self {
+            interfaces.entry(addr.interface_name.clone())
+                .or_default()
+                .push(addr);
+        }
+        interfaces
+    }
+}
+
 impl Iterator for InterfaceAddressIterator {
     type Item = InterfaceAddress;
     fn next(&mut self) -> Option<<Self as Iterator>::Item> {
@@ -144,4 +313,24 @@ mod tests {
     fn test_getifaddrs() {
         let _ = getifaddrs();
     }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[test]
+    fn test_getifaddrs_loopback() {
+        let interfaces = getifaddrs().unwrap().by_interface();
+        let lo = match interfaces.get("lo") {
+            Some(lo) => lo,
+            None => return,
+        };
+        let index = if_nametoindex("lo").unwrap();
+        assert!(lo.iter().all(|addr| addr.index == Some(index)));
+
+        let packet = lo.iter()
+            .find(|addr| addr.address.map(|a| a.family())
+                  == Some(crate::sys::socket::AddressFamily::Packet))
+            .expect("no AF_PACKET entry for lo");
+        let stats = packet.stats.expect("no link statistics for lo");
+        assert!(stats.rx_packets >= stats.rx_dropped);
+        assert_eq!(lo.iter().filter(|addr| addr.stats.is_some()).count(), 1);
+    }
 }
@@ -22,6 +22,8 @@ pub use libc;
 #[macro_use] mod macros;
 
 // Public crates
+#[cfg(target_os = "linux")]
+pub mod cgroup;
 #[cfg(not(target_os = "redox"))]
 pub mod dir;
 pub mod env;
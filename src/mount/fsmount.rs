@@ -0,0 +1,353 @@
+//! The file descriptor based mount API, which configures and attaches
+//! filesystems in separate steps instead of all at once like `mount`.
+use libc::{self, c_int, c_uint, c_void};
+use std::mem;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::ptr;
+use crate::{NixPath, Result};
+use crate::errno::Errno;
+use crate::fcntl::{at_rawfd, AtFlags};
+use crate::unistd;
+use super::MsFlags;
+
+// libc does not export the flags of the new mount API yet.
+bitflags::bitflags! {
+    /// Flags for [`fsopen`](fn.fsopen.html).
+    pub struct FsopenFlags: c_uint {
+        /// Set the close-on-exec flag on the filesystem context.
+        const FSOPEN_CLOEXEC = 0x0000_0001;
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags for [`fsmount`](fn.fsmount.html).
+    pub struct FsmountFlags: c_uint {
+        /// Set the close-on-exec flag on the mount file descriptor.
+        const FSMOUNT_CLOEXEC = 0x0000_0001;
+    }
+}
+
+bitflags::bitflags! {
+    /// Attributes of a mount, used by [`fsmount`](fn.fsmount.html) and
+    /// [`mount_setattr`](fn.mount_setattr.html).
+    pub struct MountAttr: u64 {
+        /// Mount read-only.
+        const MOUNT_ATTR_RDONLY = 0x0000_0001;
+        /// Ignore suid and sgid bits.
+        const MOUNT_ATTR_NOSUID = 0x0000_0002;
+        /// Disallow access to device special files.
+        const MOUNT_ATTR_NODEV = 0x0000_0004;
+        /// Disallow program execution.
+        const MOUNT_ATTR_NOEXEC = 0x0000_0008;
+        /// Mask for the access time settings.  Access times are updated
+        /// relative to modification times (relatime) when none of
+        /// `MOUNT_ATTR_NOATIME` and `MOUNT_ATTR_STRICTATIME` are set, so
+        /// clearing this mask selects relatime.
+        const MOUNT_ATTR__ATIME = 0x0000_0070;
+        /// Do not update access times.
+        const MOUNT_ATTR_NOATIME = 0x0000_0010;
+        /// Always update access times.
+        const MOUNT_ATTR_STRICTATIME = 0x0000_0020;
+        /// Do not update directory access times.
+        const MOUNT_ATTR_NODIRATIME = 0x0000_0080;
+        /// Map the owners of files through the user namespace given to
+        /// [`mount_setattr`](fn.mount_setattr.html).  Since Linux 5.12.
+        const MOUNT_ATTR_IDMAP = 0x0010_0000;
+        /// Do not follow symlinks.  Since Linux 5.14.
+        const MOUNT_ATTR_NOSYMFOLLOW = 0x0020_0000;
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags for [`move_mount`](fn.move_mount.html).
+    pub struct MoveMountFlags: c_uint {
+        /// Follow symlinks in the source path.
+        const MOVE_MOUNT_F_SYMLINKS = 0x0000_0001;
+        /// Follow automounts in the source path.
+        const MOVE_MOUNT_F_AUTOMOUNTS = 0x0000_0002;
+        /// Move the mount referred to by the source file descriptor if the
+        /// source path is empty.
+        const MOVE_MOUNT_F_EMPTY_PATH = 0x0000_0004;
+        /// Follow symlinks in the target path.
+        const MOVE_MOUNT_T_SYMLINKS = 0x0000_0010;
+        /// Follow automounts in the target path.
+        const MOVE_MOUNT_T_AUTOMOUNTS = 0x0000_0020;
+        /// Attach to the target file descriptor if the target path is
+        /// empty.
+        const MOVE_MOUNT_T_EMPTY_PATH = 0x0000_0040;
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags for [`open_tree`](fn.open_tree.html).
+    pub struct OpenTreeFlags: c_uint {
+        /// Create a detached copy of the mount instead of opening it.
+        const OPEN_TREE_CLONE = 0x0000_0001;
+        /// Set the close-on-exec flag on the returned file descriptor.
+        const OPEN_TREE_CLOEXEC = libc::O_CLOEXEC as c_uint;
+        /// Operate on `dirfd` itself if the path is empty.
+        const AT_EMPTY_PATH = libc::AT_EMPTY_PATH as c_uint;
+        /// Don't follow automounts in the path.
+        const AT_NO_AUTOMOUNT = libc::AT_NO_AUTOMOUNT as c_uint;
+        /// Copy the whole mount tree instead of a single mount.  Only
+        /// meaningful with `OPEN_TREE_CLONE`.
+        const AT_RECURSIVE = 0x8000;
+        /// Don't follow a symlink at the end of the path.
+        const AT_SYMLINK_NOFOLLOW = libc::AT_SYMLINK_NOFOLLOW as c_uint;
+    }
+}
+
+// Commands for fsconfig from linux/mount.h
+const FSCONFIG_SET_FLAG: c_uint = 0;
+const FSCONFIG_SET_STRING: c_uint = 1;
+const FSCONFIG_SET_FD: c_uint = 5;
+const FSCONFIG_CMD_CREATE: c_uint = 6;
+
+/// A filesystem context, as returned by [`fsopen`](fn.fsopen.html).
+///
+/// The filesystem is configured with the `fsconfig_*` functions, and then
+/// turned into a mount with [`fsmount`](fn.fsmount.html).  Reading from it
+/// returns the messages logged by the filesystem while it was being
+/// configured.  This type implements `Drop`, so the file descriptor is closed
+/// when it goes out of scope.
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct FsContext(RawFd);
+
+impl AsRawFd for FsContext {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+impl FromRawFd for FsContext {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        FsContext(fd)
+    }
+}
+
+impl IntoRawFd for FsContext {
+    fn into_raw_fd(self) -> RawFd {
+        let fd = self.0;
+        mem::forget(self);
+        fd
+    }
+}
+
+impl Drop for FsContext {
+    fn drop(&mut self) {
+        // Dropping an unfinished context discards its configuration, which
+        // can't fail; EBADF can only come from a double close.
+        let e = unistd::close(self.0);
+        if e == Err(Errno::EBADF) {
+            panic!("Closing an invalid file descriptor!");
+        };
+    }
+}
+
+/// A mount, as returned by [`fsmount`](fn.fsmount.html) and
+/// [`open_tree`](fn.open_tree.html).
+///
+/// Attach it somewhere with [`move_mount`](fn.move_mount.html).  This type
+/// implements `Drop`, so the file descriptor is closed when it goes out of
+/// scope, which unmounts the mount if it is still detached.
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct MountFd(RawFd);
+
+impl AsRawFd for MountFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+impl FromRawFd for MountFd {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        MountFd(fd)
+    }
+}
+
+impl IntoRawFd for MountFd {
+    fn into_raw_fd(self) -> RawFd {
+        let fd = self.0;
+        mem::forget(self);
+        fd
+    }
+}
+
+impl Drop for MountFd {
+    fn drop(&mut self) {
+        // A detached mount is torn down lazily once its last reference goes
+        // away, so close has nothing to report but EBADF.
+        let e = unistd::close(self.0);
+        if e == Err(Errno::EBADF) {
+            panic!("Closing an invalid file descriptor!");
+        };
+    }
+}
+
+/// Create a context for configuring a new instance of the filesystem type
+/// `fsname`
+/// ([`fsopen(2)`](https://www.man7.org/linux/man-pages/man2/fsopen.2.html)).
+pub fn fsopen<P: ?Sized + NixPath>(fsname: &P, flags: FsopenFlags) -> Result<FsContext> {
+    let res = fsname.with_nix_path(|cstr| unsafe {
+        libc::syscall(libc::SYS_fsopen, cstr.as_ptr(), flags.bits())
+    })?;
+
+    Errno::result(res).map(|fd| FsContext(fd as RawFd))
+}
+
+fn fsconfig(fs: &FsContext, cmd: c_uint, key: *const libc::c_char,
+            value: *const c_void, aux: c_int) -> Result<()> {
+    let res = unsafe {
+        libc::syscall(libc::SYS_fsconfig, fs.0, cmd, key, value, aux)
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Set the boolean parameter `key` of a filesystem context.
+pub fn fsconfig_set_flag<P: ?Sized + NixPath>(fs: &FsContext, key: &P) -> Result<()> {
+    key.with_nix_path(|key| {
+        fsconfig(fs, FSCONFIG_SET_FLAG, key.as_ptr(), ptr::null(), 0)
+    })?
+}
+
+/// Set the string parameter `key` of a filesystem context to `value`.
+pub fn fsconfig_set_string<P1, P2>(fs: &FsContext, key: &P1, value: &P2) -> Result<()>
+    where P1: ?Sized + NixPath,
+          P2: ?Sized + NixPath
+{
+    key.with_nix_path(|key| {
+        value.with_nix_path(|value| {
+            fsconfig(fs, FSCONFIG_SET_STRING, key.as_ptr(),
+                     value.as_ptr() as *const c_void, 0)
+        })
+    })??
+}
+
+/// Set the parameter `key` of a filesystem context to the open file `fd`.
+pub fn fsconfig_set_fd<P: ?Sized + NixPath>(fs: &FsContext, key: &P, fd: RawFd) -> Result<()> {
+    key.with_nix_path(|key| {
+        fsconfig(fs, FSCONFIG_SET_FD, key.as_ptr(), ptr::null(), fd)
+    })?
+}
+
+/// Create the superblock of a filesystem context once its parameters are
+/// set.
+pub fn fsconfig_create(fs: &FsContext) -> Result<()> {
+    fsconfig(fs, FSCONFIG_CMD_CREATE, ptr::null(), ptr::null(), 0)
+}
+
+/// Create a detached mount of the filesystem created in `fs`
+/// ([`fsmount(2)`](https://www.man7.org/linux/man-pages/man2/fsmount.2.html)).
+///
+/// The new mount can be attached somewhere with
+/// [`move_mount`](fn.move_mount.html).  It is unmounted when the returned
+/// `MountFd` is dropped if it wasn't attached.
+pub fn fsmount(fs: &FsContext, flags: FsmountFlags, attr: MountAttr) -> Result<MountFd> {
+    let res = unsafe {
+        libc::syscall(libc::SYS_fsmount, fs.0, flags.bits(), attr.bits() as c_uint)
+    };
+
+    Errno::result(res).map(|fd| MountFd(fd as RawFd))
+}
+
+/// Move a mount, or attach a detached one, to a new place
+/// ([`move_mount(2)`](https://www.man7.org/linux/man-pages/man2/move_mount.2.html)).
+///
+/// Paths are relative to their directory file descriptor, or to the current
+/// directory if it is `None`.
+pub fn move_mount<P1, P2>(
+    from_dirfd: Option<RawFd>,
+    from_path: &P1,
+    to_dirfd: Option<RawFd>,
+    to_path: &P2,
+    flags: MoveMountFlags,
+) -> Result<()>
+    where P1: ?Sized + NixPath,
+          P2: ?Sized + NixPath
+{
+    let res = from_path.with_nix_path(|from| {
+        to_path.with_nix_path(|to| unsafe {
+            libc::syscall(libc::SYS_move_mount,
+                          at_rawfd(from_dirfd), from.as_ptr(),
+                          at_rawfd(to_dirfd), to.as_ptr(),
+                          flags.bits())
+        })
+    })??;
+
+    Errno::result(res).map(drop)
+}
+
+/// Open a mount, or with `OPEN_TREE_CLONE` create a detached copy of it
+/// ([`open_tree(2)`](https://www.man7.org/linux/man-pages/man2/open_tree.2.html)).
+pub fn open_tree<P: ?Sized + NixPath>(dirfd: Option<RawFd>, path: &P, flags: OpenTreeFlags)
+    -> Result<MountFd>
+{
+    let res = path.with_nix_path(|cstr| unsafe {
+        libc::syscall(libc::SYS_open_tree, at_rawfd(dirfd), cstr.as_ptr(), flags.bits())
+    })?;
+
+    Errno::result(res).map(|fd| MountFd(fd as RawFd))
+}
+
+/// Changes to make to the properties of a mount with
+/// [`mount_setattr`](fn.mount_setattr.html).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct MountSetattr {
+    /// Attributes to set.
+    pub set: MountAttr,
+    /// Attributes to clear.
+    pub clear: MountAttr,
+    /// New propagation type: one of `MS_SHARED`, `MS_SLAVE`, `MS_PRIVATE`
+    /// or `MS_UNBINDABLE`, or empty to leave it unchanged.
+    pub propagation: MsFlags,
+    /// User namespace to map file owners through with `MOUNT_ATTR_IDMAP`.
+    pub userns_fd: Option<RawFd>,
+}
+
+impl Default for MountSetattr {
+    fn default() -> Self {
+        MountSetattr {
+            set: MountAttr::empty(),
+            clear: MountAttr::empty(),
+            propagation: MsFlags::empty(),
+            userns_fd: None,
+        }
+    }
+}
+
+// `struct mount_attr` from linux/mount.h, which libc does not export yet
+#[repr(C)]
+struct mount_attr {
+    attr_set: u64,
+    attr_clr: u64,
+    propagation: u64,
+    userns_fd: u64,
+}
+
+/// Change the properties of a mount, or with `AT_RECURSIVE` of a whole
+/// mount tree
+/// ([`mount_setattr(2)`](https://www.man7.org/linux/man-pages/man2/mount_setattr.2.html)).
+///
+/// `flags` may contain `AT_EMPTY_PATH`, `AT_NO_AUTOMOUNT`,
+/// `AT_SYMLINK_NOFOLLOW` and `AT_RECURSIVE`.
+pub fn mount_setattr<P: ?Sized + NixPath>(
+    dirfd: Option<RawFd>,
+    path: &P,
+    flags: AtFlags,
+    attr: &MountSetattr,
+) -> Result<()> {
+    let mut attr = mount_attr {
+        attr_set: attr.set.bits(),
+        attr_clr: attr.clear.bits(),
+        propagation: attr.propagation.bits() as u64,
+        userns_fd: attr.userns_fd.map_or(0, |fd| fd as u64),
+    };
+    let res = path.with_nix_path(|cstr| unsafe {
+        libc::syscall(libc::SYS_mount_setattr, at_rawfd(dirfd), cstr.as_ptr(),
+                      flags.bits() as c_uint, &mut attr as *mut mount_attr,
+                      mem::size_of::<mount_attr>())
+    })?;
+
+    Errno::result(res).map(drop)
+}
@@ -4,6 +4,18 @@ mod linux;
 #[cfg(any(target_os = "android", target_os = "linux"))]
 pub use self::linux::*;
 
+#[cfg(target_os = "linux")]
+mod fsmount;
+
+#[cfg(target_os = "linux")]
+pub use self::fsmount::*;
+
+#[cfg(target_os = "linux")]
+mod mountinfo;
+
+#[cfg(target_os = "linux")]
+pub use self::mountinfo::*;
+
 #[cfg(any(target_os = "dragonfly",
           target_os = "freebsd",
           target_os = "macos",
@@ -0,0 +1,256 @@
+use libc::dev_t;
+use std::ffi::{OsStr, OsString};
+use std::os::unix::ffi::OsStrExt;
+use std::path::PathBuf;
+use std::str::{self, FromStr};
+use crate::{Error, Result};
+use crate::errno::Errno;
+use crate::fcntl::{self, OFlag};
+use crate::sys::stat::{makedev, Mode};
+use crate::unistd::{self, Pid};
+use super::MsFlags;
+
+/// A mount, as described by a line of `/proc/<pid>/mountinfo`.
+///
+/// See [`proc(5)`](https://man7.org/linux/man-pages/man5/proc.5.html) for
+/// details on each field.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct MountEntry {
+    /// Unique ID of the mount.
+    pub mount_id: u32,
+    /// ID of the parent mount, or of the mount itself for the root of the
+    /// mount namespace.
+    pub parent_id: u32,
+    /// Device number of the filesystem.
+    pub dev: dev_t,
+    /// Directory of the filesystem that is the root of the mount.
+    pub root: PathBuf,
+    /// Where the filesystem is mounted, relative to the root directory of
+    /// the process.
+    pub mount_point: PathBuf,
+    /// Per-mount options, like `MS_RDONLY` or `MS_NOEXEC`.
+    pub flags: MsFlags,
+    /// Peer group of the mount, if it is shared.
+    pub shared: Option<u32>,
+    /// Peer group the mount receives events from, if it is a slave.
+    pub master: Option<u32>,
+    /// Closest peer group the mount receives events from that is visible to
+    /// the process, if it differs from `master`.
+    pub propagate_from: Option<u32>,
+    /// Whether the mount is unbindable.
+    pub unbindable: bool,
+    /// Type of the filesystem, like `ext4` or `fuse.sshfs`.
+    pub fs_type: OsString,
+    /// Filesystem-specific source, like a device path.
+    pub source: OsString,
+    /// Options of the filesystem, shared by all of its mounts.
+    pub super_options: Vec<OsString>,
+}
+
+// Decode the octal escapes the kernel uses for space, tab, newline and
+// backslash.
+fn unescape(field: &[u8]) -> OsString {
+    let mut out = Vec::with_capacity(field.len());
+    let mut i = 0;
+    while i < field.len() {
+        let escape = field.get(i + 1..i + 4)
+            .filter(|_| field[i] == b'\\')
+            .and_then(|digits| str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+        match escape {
+            Some(byte) => {
+                out.push(byte);
+                i += 4;
+            },
+            None => {
+                out.push(field[i]);
+                i += 1;
+            },
+        }
+    }
+    OsString::from(OsStr::from_bytes(&out))
+}
+
+fn parse_num<T: FromStr>(field: &[u8]) -> Result<T> {
+    str::from_utf8(field).ok()
+        .and_then(|s| s.parse().ok())
+        .ok_or(Errno::EINVAL)
+}
+
+impl MountEntry {
+    /// Parse one line of a mountinfo file, without its newline.
+    pub fn from_bytes(line: &[u8]) -> Result<MountEntry> {
+        let mut fields = line.split(|&b| b == b' ');
+        let mut next = || fields.next().ok_or(Errno::EINVAL);
+
+        let mount_id = parse_num(next()?)?;
+        let parent_id = parse_num(next()?)?;
+        let mut devno = next()?.splitn(2, |&b| b == b':');
+        let major = parse_num(devno.next().ok_or(Errno::EINVAL)?)?;
+        let minor = parse_num(devno.next().ok_or(Errno::EINVAL)?)?;
+        let root = PathBuf::from(unescape(next()?));
+        let mount_point = PathBuf::from(unescape(next()?));
+
+        let mut flags = MsFlags::empty();
+        for option in next()?.split(|&b| b == b',') {
+            flags |= match option {
+                b"ro" => MsFlags::MS_RDONLY,
+                b"nosuid" => MsFlags::MS_NOSUID,
+                b"nodev" => MsFlags::MS_NODEV,
+                b"noexec" => MsFlags::MS_NOEXEC,
+                b"noatime" => MsFlags::MS_NOATIME,
+                b"nodiratime" => MsFlags::MS_NODIRATIME,
+                b"relatime" => MsFlags::MS_RELATIME,
+                _ => MsFlags::empty(),
+            };
+        }
+
+        let mut entry = MountEntry {
+            mount_id,
+            parent_id,
+            dev: makedev(major, minor),
+            root,
+            mount_point,
+            flags,
+            shared: None,
+            master: None,
+            propagate_from: None,
+            unbindable: false,
+            fs_type: OsString::new(),
+            source: OsString::new(),
+            super_options: Vec::new(),
+        };
+
+        // Optional fields, terminated by a single hyphen
+        loop {
+            let field = next()?;
+            if field == b"-" {
+                break;
+            }
+            let mut tag = field.splitn(2, |&b| b == b':');
+            match (tag.next(), tag.next()) {
+                (Some(b"shared"), Some(id)) => entry.shared = Some(parse_num(id)?),
+                (Some(b"master"), Some(id)) => entry.master = Some(parse_num(id)?),
+                (Some(b"propagate_from"), Some(id)) => {
+                    entry.propagate_from = Some(parse_num(id)?)
+                },
+                (Some(b"unbindable"), None) => entry.unbindable = true,
+                // Fields added by future kernels
+                _ => (),
+            }
+        }
+
+        entry.fs_type = unescape(next()?);
+        entry.source = unescape(next()?);
+        entry.super_options = next()?
+            .split(|&b| b == b',')
+            .map(unescape)
+            .collect();
+        Ok(entry)
+    }
+}
+
+impl FromStr for MountEntry {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<MountEntry> {
+        MountEntry::from_bytes(s.as_bytes())
+    }
+}
+
+/// Iterator over the mounts of a mount namespace, as listed in
+/// `/proc/<pid>/mountinfo`.
+///
+/// The whole file is read when the iterator is created, so that the entries
+/// form a consistent snapshot even if mounts change while iterating.
+///
+/// # Example
+///
+/// ```
+/// use nix::mount::MountInfo;
+///
+/// for mount in MountInfo::new().unwrap() {
+///     let mount = mount.unwrap();
+///     println!("{:?} on {}", mount.source, mount.mount_point.display());
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MountInfo {
+    data: Vec<u8>,
+    pos: usize,
+}
+
+impl MountInfo {
+    /// Read the mounts of the calling process.
+    pub fn new() -> Result<MountInfo> {
+        MountInfo::read("/proc/self/mountinfo")
+    }
+
+    /// Read the mounts seen by the process `pid`.
+    pub fn for_pid(pid: Pid) -> Result<MountInfo> {
+        MountInfo::read(format!("/proc/{}/mountinfo", pid).as_str())
+    }
+
+    fn read(path: &str) -> Result<MountInfo> {
+        let fd = fcntl::open(path, OFlag::O_RDONLY | OFlag::O_CLOEXEC, Mode::empty())?;
+        let res = unistd::read_to_end(fd);
+        unistd::close(fd)?;
+        res.map(|data| MountInfo { data, pos: 0 })
+    }
+}
+
+impl Iterator for MountInfo {
+    type Item = Result<MountEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.data[self.pos..];
+        if rest.is_empty() {
+            return None;
+        }
+        let len = rest.iter().position(|&b| b == b'\n').unwrap_or(rest.len());
+        self.pos += (len + 1).min(rest.len());
+        Some(MountEntry::from_bytes(&rest[..len]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_proc_example() {
+        // The example from proc(5)
+        let entry: MountEntry =
+            "36 35 98:0 /mnt1 /mnt2 rw,noatime master:1 - ext3 /dev/root rw,errors=continue"
+            .parse().unwrap();
+        assert_eq!(entry.mount_id, 36);
+        assert_eq!(entry.parent_id, 35);
+        assert_eq!(entry.dev, makedev(98, 0));
+        assert_eq!(entry.root, PathBuf::from("/mnt1"));
+        assert_eq!(entry.mount_point, PathBuf::from("/mnt2"));
+        assert_eq!(entry.flags, MsFlags::MS_NOATIME);
+        assert_eq!(entry.shared, None);
+        assert_eq!(entry.master, Some(1));
+        assert!(!entry.unbindable);
+        assert_eq!(entry.fs_type, "ext3");
+        assert_eq!(entry.source, "/dev/root");
+        assert_eq!(entry.super_options, vec!["rw", "errors=continue"]);
+    }
+
+    #[test]
+    fn parse_escapes_and_tags() {
+        let entry = MountEntry::from_bytes(
+            b"42 1 0:51 / /tmp/a\\040dir\\134x ro,nosuid shared:7 unbindable - tmpfs tmpfs ro")
+            .unwrap();
+        assert_eq!(entry.mount_point, PathBuf::from("/tmp/a dir\\x"));
+        assert_eq!(entry.flags, MsFlags::MS_RDONLY | MsFlags::MS_NOSUID);
+        assert_eq!(entry.shared, Some(7));
+        assert!(entry.unbindable);
+        assert_eq!(entry.super_options, vec!["ro"]);
+    }
+
+    #[test]
+    fn parse_truncated() {
+        assert_eq!(MountEntry::from_bytes(b"42 1 0:51 / /tmp rw"), Err(Errno::EINVAL));
+    }
+}
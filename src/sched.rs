@@ -6,40 +6,57 @@ pub use self::sched_linux_like::*;
 #[cfg(any(target_os = "android", target_os = "linux"))]
 mod sched_linux_like {
     use crate::errno::Errno;
-    use libc::{self, c_int, c_void};
+    use crate::fcntl::{self, OFlag};
+    use crate::sys::stat::{fstat, Mode};
+    use crate::unistd;
+    use crate::sys::time::TimeSpec;
+    use libc::{self, c_int, c_uint, c_void};
     use std::mem;
     use std::option::Option;
-    use std::os::unix::io::RawFd;
-    use crate::unistd::Pid;
+    use std::ptr;
+    use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+    #[cfg(target_os = "linux")]
+    use crate::sys::signal::Signal;
+    #[cfg(target_os = "linux")]
+    use crate::unistd::ForkResult;
+    use crate::unistd::{Gid, Pid, Uid};
     use crate::{Error, Result};
 
     // For some functions taking with a parameter of type CloneFlags,
     // only a subset of these flags have an effect.
-    libc_bitflags! {
+    bitflags::bitflags! {
         pub struct CloneFlags: c_int {
-            CLONE_VM;
-            CLONE_FS;
-            CLONE_FILES;
-            CLONE_SIGHAND;
-            CLONE_PTRACE;
-            CLONE_VFORK;
-            CLONE_PARENT;
-            CLONE_THREAD;
-            CLONE_NEWNS;
-            CLONE_SYSVSEM;
-            CLONE_SETTLS;
-            CLONE_PARENT_SETTID;
-            CLONE_CHILD_CLEARTID;
-            CLONE_DETACHED;
-            CLONE_UNTRACED;
-            CLONE_CHILD_SETTID;
-            CLONE_NEWCGROUP;
-            CLONE_NEWUTS;
-            CLONE_NEWIPC;
-            CLONE_NEWUSER;
-            CLONE_NEWPID;
-            CLONE_NEWNET;
-            CLONE_IO;
+            const CLONE_VM = libc::CLONE_VM;
+            const CLONE_FS = libc::CLONE_FS;
+            const CLONE_FILES = libc::CLONE_FILES;
+            const CLONE_SIGHAND = libc::CLONE_SIGHAND;
+            const CLONE_PTRACE = libc::CLONE_PTRACE;
+            const CLONE_VFORK = libc::CLONE_VFORK;
+            const CLONE_PARENT = libc::CLONE_PARENT;
+            const CLONE_THREAD = libc::CLONE_THREAD;
+            const CLONE_NEWNS = libc::CLONE_NEWNS;
+            const CLONE_SYSVSEM = libc::CLONE_SYSVSEM;
+            const CLONE_SETTLS = libc::CLONE_SETTLS;
+            const CLONE_PARENT_SETTID = libc::CLONE_PARENT_SETTID;
+            const CLONE_CHILD_CLEARTID = libc::CLONE_CHILD_CLEARTID;
+            const CLONE_DETACHED = libc::CLONE_DETACHED;
+            const CLONE_UNTRACED = libc::CLONE_UNTRACED;
+            const CLONE_CHILD_SETTID = libc::CLONE_CHILD_SETTID;
+            const CLONE_NEWCGROUP = libc::CLONE_NEWCGROUP;
+            const CLONE_NEWUTS = libc::CLONE_NEWUTS;
+            const CLONE_NEWIPC = libc::CLONE_NEWIPC;
+            const CLONE_NEWUSER = libc::CLONE_NEWUSER;
+            const CLONE_NEWPID = libc::CLONE_NEWPID;
+            const CLONE_NEWNET = libc::CLONE_NEWNET;
+            const CLONE_IO = libc::CLONE_IO;
+            /// Return a PID file descriptor for the child.  Set by
+            /// [`CloneArgs::pidfd`](struct.CloneArgs.html#method.pidfd).
+            // libc does not export CLONE_PIDFD and CLONE_NEWTIME yet
+            #[cfg(target_os = "linux")]
+            const CLONE_PIDFD = 0x1000;
+            /// Create the child in a new time namespace.
+            #[cfg(target_os = "linux")]
+            const CLONE_NEWTIME = 0x80;
         }
     }
 
@@ -108,6 +125,176 @@ mod sched_linux_like {
         }
     }
 
+    /// A dynamically sized set of CPUs.
+    ///
+    /// Unlike [`CpuSet`](struct.CpuSet.html), it is not limited to
+    /// `CPU_SETSIZE` CPUs: it grows as CPUs are added, and
+    /// [`sched_getaffinity_mask`](fn.sched_getaffinity_mask.html) returns a
+    /// mask of the size the kernel uses.
+    ///
+    /// Its `Display` and `FromStr` implementations use the list format of
+    /// the kernel, as in `/sys/devices/system/cpu/online`.  Parsing rejects
+    /// CPUs that no kernel can have, above 8191, with `EINVAL`:
+    ///
+    /// ```
+    /// use nix::sched::CpuMask;
+    ///
+    /// let mask: CpuMask = "0-3,8,10-11".parse().unwrap();
+    /// assert_eq!(mask.count(), 7);
+    /// assert!(mask.is_set(10));
+    /// assert_eq!(mask.to_string(), "0-3,8,10-11");
+    /// ```
+    #[derive(Clone, Debug, Default)]
+    pub struct CpuMask {
+        bits: Vec<libc::c_ulong>,
+    }
+
+    impl CpuMask {
+        const BITS: usize = 8 * mem::size_of::<libc::c_ulong>();
+        // The largest NR_CPUS the kernel can be configured with, which
+        // keeps `FromStr` from allocating huge masks for bogus input
+        const MAX_CPUS: usize = 8192;
+
+        /// Create a new and empty CpuMask.
+        pub fn new() -> CpuMask {
+            CpuMask { bits: Vec::new() }
+        }
+
+        /// Test to see if a CPU is in the CpuMask.
+        pub fn is_set(&self, cpu: usize) -> bool {
+            self.bits.get(cpu / Self::BITS)
+                .map(|word| word & (1 << (cpu % Self::BITS)) != 0) == Some(true)
+        }
+
+        /// Add a CPU to the CpuMask, growing it if needed.
+        pub fn set(&mut self, cpu: usize) {
+            let word = cpu / Self::BITS;
+            if word >= self.bits.len() {
+                self.bits.resize(word + 1, 0);
+            }
+            self.bits[word] |= 1 << (cpu % Self::BITS);
+        }
+
+        /// Remove a CPU from the CpuMask.
+        pub fn unset(&mut self, cpu: usize) {
+            if let Some(word) = self.bits.get_mut(cpu / Self::BITS) {
+                *word &= !(1 << (cpu % Self::BITS));
+            }
+        }
+
+        /// Number of CPUs in the CpuMask.
+        pub fn count(&self) -> usize {
+            self.bits.iter().map(|word| word.count_ones() as usize).sum()
+        }
+
+        /// Whether the CpuMask contains no CPU.
+        pub fn is_empty(&self) -> bool {
+            self.bits.iter().all(|&word| word == 0)
+        }
+
+        /// Iterate over the CPUs in the CpuMask, in increasing order.
+        pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+            (0..self.bits.len() * Self::BITS).filter(move |&cpu| self.is_set(cpu))
+        }
+
+        /// The CPUs that are in either CpuMask.
+        pub fn union(&self, other: &CpuMask) -> CpuMask {
+            let (long, short) = if self.bits.len() >= other.bits.len() {
+                (self, other)
+            } else {
+                (other, self)
+            };
+            let mut result = long.clone();
+            for (word, other) in result.bits.iter_mut().zip(&short.bits) {
+                *word |= other;
+            }
+            result
+        }
+
+        /// The CPUs that are in both CpuMasks.
+        pub fn intersection(&self, other: &CpuMask) -> CpuMask {
+            CpuMask {
+                bits: self.bits.iter().zip(&other.bits).map(|(a, b)| a & b).collect(),
+            }
+        }
+    }
+
+    impl PartialEq for CpuMask {
+        // Masks of different sizes are equal if they contain the same CPUs
+        fn eq(&self, other: &CpuMask) -> bool {
+            let len = self.bits.len().max(other.bits.len());
+            (0..len).all(|i| {
+                self.bits.get(i).unwrap_or(&0) == other.bits.get(i).unwrap_or(&0)
+            })
+        }
+    }
+
+    impl Eq for CpuMask {}
+
+    impl From<CpuSet> for CpuMask {
+        fn from(cpuset: CpuSet) -> CpuMask {
+            let mut mask = CpuMask::new();
+            for cpu in (0..CpuSet::count()).filter(|&cpu| cpuset.is_set(cpu) == Ok(true)) {
+                mask.set(cpu);
+            }
+            mask
+        }
+    }
+
+    impl std::iter::FromIterator<usize> for CpuMask {
+        fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> CpuMask {
+            let mut mask = CpuMask::new();
+            for cpu in iter {
+                mask.set(cpu);
+            }
+            mask
+        }
+    }
+
+    impl std::str::FromStr for CpuMask {
+        type Err = Error;
+
+        fn from_str(s: &str) -> Result<CpuMask> {
+            let mut mask = CpuMask::new();
+            for range in s.trim().split(',').filter(|range| !range.is_empty()) {
+                let mut bounds = range.splitn(2, '-')
+                    .map(|cpu| cpu.parse::<usize>().map_err(|_| Errno::EINVAL));
+                let first = bounds.next().unwrap_or(Err(Errno::EINVAL))?;
+                let last = bounds.next().unwrap_or(Ok(first))?;
+                if last < first || last >= Self::MAX_CPUS {
+                    return Err(Errno::EINVAL);
+                }
+                for cpu in first..=last {
+                    mask.set(cpu);
+                }
+            }
+            Ok(mask)
+        }
+    }
+
+    impl std::fmt::Display for CpuMask {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            let mut cpus = self.iter().peekable();
+            let mut first = true;
+            while let Some(start) = cpus.next() {
+                let mut end = start;
+                while cpus.peek() == Some(&(end + 1)) {
+                    end = cpus.next().unwrap();
+                }
+                if !first {
+                    f.write_str(",")?;
+                }
+                first = false;
+                if start == end {
+                    write!(f, "{}", start)?;
+                } else {
+                    write!(f, "{}-{}", start, end)?;
+                }
+            }
+            Ok(())
+        }
+    }
+
     /// `sched_setaffinity` set a thread's CPU affinity mask
     /// ([`sched_setaffinity(2)`](https://man7.org/linux/man-pages/man2/sched_setaffinity.2.html))
     ///
@@ -176,6 +363,356 @@ mod sched_linux_like {
         Errno::result(res).and(Ok(cpuset))
     }
 
+    /// `sched_setaffinity` set a thread's CPU affinity mask from a
+    /// [`CpuMask`](struct.CpuMask.html)
+    /// ([`sched_setaffinity(2)`](https://man7.org/linux/man-pages/man2/sched_setaffinity.2.html))
+    ///
+    /// `pid` is the thread ID to update.
+    /// If pid is zero, then the calling thread is updated.
+    pub fn sched_setaffinity_mask(pid: Pid, mask: &CpuMask) -> Result<()> {
+        let res = unsafe {
+            libc::syscall(libc::SYS_sched_setaffinity,
+                          pid.as_raw(),
+                          mask.bits.len() * mem::size_of::<libc::c_ulong>(),
+                          mask.bits.as_ptr())
+        };
+
+        Errno::result(res).map(drop)
+    }
+
+    /// `sched_getaffinity` get a thread's CPU affinity mask as a
+    /// [`CpuMask`](struct.CpuMask.html) of the size the kernel uses
+    /// ([`sched_getaffinity(2)`](https://man7.org/linux/man-pages/man2/sched_getaffinity.2.html))
+    ///
+    /// `pid` is the thread ID to check.
+    /// If pid is zero, then the calling thread is checked.
+    pub fn sched_getaffinity_mask(pid: Pid) -> Result<CpuMask> {
+        let mut bits: Vec<libc::c_ulong> = vec![0; CpuSet::count() / CpuMask::BITS];
+        loop {
+            // Unlike the libc wrapper, the system call returns the size of
+            // the kernel's mask, and fails if the buffer is smaller.
+            let res = unsafe {
+                libc::syscall(libc::SYS_sched_getaffinity,
+                              pid.as_raw(),
+                              bits.len() * mem::size_of::<libc::c_ulong>(),
+                              bits.as_mut_ptr())
+            };
+            match Errno::result(res) {
+                Ok(size) => {
+                    bits.truncate(size as usize / mem::size_of::<libc::c_ulong>());
+                    return Ok(CpuMask { bits });
+                },
+                Err(Errno::EINVAL) => {
+                    let len = bits.len();
+                    bits.resize(len * 2, 0);
+                },
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Return the CPU the calling thread is running on
+    /// ([`sched_getcpu(3)`](https://man7.org/linux/man-pages/man3/sched_getcpu.3.html)).
+    ///
+    /// The thread may already have been moved to another CPU by the time
+    /// this returns.
+    pub fn sched_getcpu() -> Result<usize> {
+        let res = unsafe { libc::sched_getcpu() };
+
+        Errno::result(res).map(|cpu| cpu as usize)
+    }
+
+    /// Return the CPU the calling thread is running on, and the NUMA node
+    /// of that CPU, as `(cpu, node)`
+    /// ([`getcpu(2)`](https://man7.org/linux/man-pages/man2/getcpu.2.html)).
+    pub fn getcpu() -> Result<(usize, usize)> {
+        let mut cpu: c_uint = 0;
+        let mut node: c_uint = 0;
+        let res = unsafe {
+            libc::syscall(libc::SYS_getcpu, &mut cpu as *mut c_uint,
+                          &mut node as *mut c_uint, ptr::null_mut::<c_void>())
+        };
+
+        Errno::result(res).map(|_| (cpu as usize, node as usize))
+    }
+
+    /// Scheduling policies, as used by
+    /// [`sched_setscheduler`](fn.sched_setscheduler.html) and
+    /// [`sched_setattr`](fn.sched_setattr.html)
+    /// ([`sched(7)`](https://man7.org/linux/man-pages/man7/sched.7.html)).
+    #[repr(i32)]
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub enum SchedPolicy {
+        /// The default time-sharing policy.
+        SCHED_OTHER = libc::SCHED_OTHER,
+        /// First-in, first-out real-time policy.
+        SCHED_FIFO = libc::SCHED_FIFO,
+        /// Round-robin real-time policy.
+        SCHED_RR = libc::SCHED_RR,
+        /// Time-sharing policy for CPU-bound batch jobs.
+        SCHED_BATCH = libc::SCHED_BATCH,
+        /// Policy for jobs that should only run when nothing else wants to.
+        SCHED_IDLE = libc::SCHED_IDLE,
+        /// Earliest deadline first policy, which can only be set with
+        /// `sched_setattr`.  libc does not export it yet on Linux.
+        SCHED_DEADLINE = 6,
+    }
+
+    impl SchedPolicy {
+        fn from_c_int(policy: c_int) -> Result<SchedPolicy> {
+            Ok(match policy {
+                libc::SCHED_OTHER => SchedPolicy::SCHED_OTHER,
+                libc::SCHED_FIFO => SchedPolicy::SCHED_FIFO,
+                libc::SCHED_RR => SchedPolicy::SCHED_RR,
+                libc::SCHED_BATCH => SchedPolicy::SCHED_BATCH,
+                libc::SCHED_IDLE => SchedPolicy::SCHED_IDLE,
+                6 => SchedPolicy::SCHED_DEADLINE,
+                _ => return Err(Errno::EINVAL),
+            })
+        }
+    }
+
+    /// Set the scheduling policy of a thread, and its priority for the
+    /// real-time policies
+    /// ([`sched_setscheduler(2)`](https://man7.org/linux/man-pages/man2/sched_setscheduler.2.html)).
+    ///
+    /// If `pid` is zero, the calling thread is updated.  `priority` must be
+    /// zero for the other policies.
+    pub fn sched_setscheduler(pid: Pid, policy: SchedPolicy, priority: c_int) -> Result<()> {
+        let param = libc::sched_param { sched_priority: priority };
+        let res = unsafe {
+            libc::sched_setscheduler(pid.into(), policy as c_int, &param)
+        };
+
+        Errno::result(res).map(drop)
+    }
+
+    /// Get the scheduling policy of a thread
+    /// ([`sched_getscheduler(2)`](https://man7.org/linux/man-pages/man2/sched_getscheduler.2.html)).
+    ///
+    /// If `pid` is zero, the policy of the calling thread is returned.  Use
+    /// [`sched_getattr`](fn.sched_getattr.html) to also learn whether
+    /// `SCHED_FLAG_RESET_ON_FORK` is set.
+    pub fn sched_getscheduler(pid: Pid) -> Result<SchedPolicy> {
+        let res = unsafe { libc::sched_getscheduler(pid.into()) };
+
+        Errno::result(res)
+            .and_then(|policy| SchedPolicy::from_c_int(policy & !libc::SCHED_RESET_ON_FORK))
+    }
+
+    /// Lowest priority of the real-time policy `policy`
+    /// ([`sched_get_priority_min(2)`](https://man7.org/linux/man-pages/man2/sched_get_priority_min.2.html)).
+    pub fn sched_get_priority_min(policy: SchedPolicy) -> Result<c_int> {
+        let res = unsafe { libc::sched_get_priority_min(policy as c_int) };
+
+        Errno::result(res)
+    }
+
+    /// Highest priority of the real-time policy `policy`
+    /// ([`sched_get_priority_max(2)`](https://man7.org/linux/man-pages/man2/sched_get_priority_max.2.html)).
+    pub fn sched_get_priority_max(policy: SchedPolicy) -> Result<c_int> {
+        let res = unsafe { libc::sched_get_priority_max(policy as c_int) };
+
+        Errno::result(res)
+    }
+
+    /// Time quantum of a thread under the `SCHED_RR` policy
+    /// ([`sched_rr_get_interval(2)`](https://man7.org/linux/man-pages/man2/sched_rr_get_interval.2.html)).
+    pub fn sched_rr_get_interval(pid: Pid) -> Result<TimeSpec> {
+        let mut interval = mem::MaybeUninit::<libc::timespec>::uninit();
+        let res = unsafe { libc::sched_rr_get_interval(pid.into(), interval.as_mut_ptr()) };
+
+        Errno::result(res).map(|_| TimeSpec::from(unsafe { interval.assume_init() }))
+    }
+
+    // libc does not export the sched_setattr flags yet
+    bitflags::bitflags! {
+        /// Flags for [`SchedAttr`](struct.SchedAttr.html).
+        pub struct SchedFlags: u64 {
+            /// Children created by `fork` don't inherit privileged policies.
+            const SCHED_FLAG_RESET_ON_FORK = 0x01;
+            /// Let `SCHED_DEADLINE` threads reclaim unused bandwidth.
+            const SCHED_FLAG_RECLAIM = 0x02;
+            /// Send `SIGXCPU` to `SCHED_DEADLINE` threads that overrun.
+            const SCHED_FLAG_DL_OVERRUN = 0x04;
+            /// Leave the policy unchanged.
+            const SCHED_FLAG_KEEP_POLICY = 0x08;
+            /// Leave the policy parameters unchanged.
+            const SCHED_FLAG_KEEP_PARAMS = 0x10;
+            /// Set the minimum utilization clamp from `util_min`.
+            const SCHED_FLAG_UTIL_CLAMP_MIN = 0x20;
+            /// Set the maximum utilization clamp from `util_max`.
+            const SCHED_FLAG_UTIL_CLAMP_MAX = 0x40;
+        }
+    }
+
+    // `struct sched_attr` from linux/sched/types.h, which libc does not
+    // export yet
+    #[repr(C)]
+    struct sched_attr {
+        size: u32,
+        sched_policy: u32,
+        sched_flags: u64,
+        sched_nice: i32,
+        sched_priority: u32,
+        sched_runtime: u64,
+        sched_deadline: u64,
+        sched_period: u64,
+        sched_util_min: u32,
+        sched_util_max: u32,
+    }
+
+    /// Scheduling policy and parameters of a thread, as used by
+    /// [`sched_setattr`](fn.sched_setattr.html) and
+    /// [`sched_getattr`](fn.sched_getattr.html).
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    pub struct SchedAttr {
+        /// Scheduling policy.
+        pub policy: SchedPolicy,
+        /// Flags changing how the policy behaves.
+        pub flags: SchedFlags,
+        /// Nice value for `SCHED_OTHER` and `SCHED_BATCH`.
+        pub nice: i32,
+        /// Priority for `SCHED_FIFO` and `SCHED_RR`.
+        pub priority: u32,
+        /// Run time guaranteed in each period under `SCHED_DEADLINE`, in
+        /// nanoseconds.
+        pub runtime: u64,
+        /// Relative deadline under `SCHED_DEADLINE`, in nanoseconds.
+        pub deadline: u64,
+        /// Period under `SCHED_DEADLINE`, in nanoseconds.
+        pub period: u64,
+        /// Minimum utilization clamp, from 0 to 1024.  Since Linux 5.3.
+        pub util_min: u32,
+        /// Maximum utilization clamp, from 0 to 1024.  Since Linux 5.3.
+        pub util_max: u32,
+    }
+
+    impl SchedAttr {
+        /// Attributes for `policy`, with every parameter set to zero.
+        pub fn new(policy: SchedPolicy) -> Self {
+            SchedAttr {
+                policy,
+                flags: SchedFlags::empty(),
+                nice: 0,
+                priority: 0,
+                runtime: 0,
+                deadline: 0,
+                period: 0,
+                util_min: 0,
+                util_max: 0,
+            }
+        }
+    }
+
+    /// Set the scheduling policy and parameters of a thread
+    /// ([`sched_setattr(2)`](https://man7.org/linux/man-pages/man2/sched_setattr.2.html)).
+    ///
+    /// If `pid` is zero, the calling thread is updated.
+    pub fn sched_setattr(pid: Pid, attr: &SchedAttr) -> Result<()> {
+        let mut raw = sched_attr {
+            size: mem::size_of::<sched_attr>() as u32,
+            sched_policy: attr.policy as u32,
+            sched_flags: attr.flags.bits(),
+            sched_nice: attr.nice,
+            sched_priority: attr.priority,
+            sched_runtime: attr.runtime,
+            sched_deadline: attr.deadline,
+            sched_period: attr.period,
+            sched_util_min: attr.util_min,
+            sched_util_max: attr.util_max,
+        };
+        let res = unsafe {
+            libc::syscall(libc::SYS_sched_setattr, pid.as_raw(),
+                          &mut raw as *mut sched_attr, 0)
+        };
+
+        Errno::result(res).map(drop)
+    }
+
+    /// Get the scheduling policy and parameters of a thread
+    /// ([`sched_getattr(2)`](https://man7.org/linux/man-pages/man2/sched_getattr.2.html)).
+    ///
+    /// If `pid` is zero, the attributes of the calling thread are returned.
+    pub fn sched_getattr(pid: Pid) -> Result<SchedAttr> {
+        // Older kernels fill in less than the whole structure
+        let mut raw: sched_attr = unsafe { mem::zeroed() };
+        let res = unsafe {
+            libc::syscall(libc::SYS_sched_getattr, pid.as_raw(),
+                          &mut raw as *mut sched_attr,
+                          mem::size_of::<sched_attr>() as c_uint, 0)
+        };
+        Errno::result(res)?;
+
+        Ok(SchedAttr {
+            policy: SchedPolicy::from_c_int(raw.sched_policy as c_int)?,
+            flags: SchedFlags::from_bits_truncate(raw.sched_flags),
+            nice: raw.sched_nice,
+            priority: raw.sched_priority,
+            runtime: raw.sched_runtime,
+            deadline: raw.sched_deadline,
+            period: raw.sched_period,
+            util_min: raw.sched_util_min,
+            util_max: raw.sched_util_max,
+        })
+    }
+
+    /// Processes whose nice value is read by
+    /// [`getpriority`](fn.getpriority.html) or changed by
+    /// [`setpriority`](fn.setpriority.html).
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    pub enum PriorityTarget {
+        /// A single process, or the calling process if the ID is zero.
+        Process(Pid),
+        /// A process group, or the one of the calling process if the ID is
+        /// zero.
+        ProcessGroup(Pid),
+        /// All processes of a user, or of the calling process's real user if
+        /// the ID is zero.
+        User(Uid),
+    }
+
+    impl PriorityTarget {
+        fn which_who(self) -> (c_int, libc::id_t) {
+            match self {
+                PriorityTarget::Process(pid) => {
+                    (libc::PRIO_PROCESS as c_int, pid.as_raw() as libc::id_t)
+                },
+                PriorityTarget::ProcessGroup(pid) => {
+                    (libc::PRIO_PGRP as c_int, pid.as_raw() as libc::id_t)
+                },
+                PriorityTarget::User(uid) => {
+                    (libc::PRIO_USER as c_int, uid.as_raw() as libc::id_t)
+                },
+            }
+        }
+    }
+
+    /// Get the nice value of a process, process group or user, from -20
+    /// (highest priority) to 19
+    /// ([`getpriority(2)`](https://man7.org/linux/man-pages/man2/getpriority.2.html)).
+    ///
+    /// For several processes, the highest priority among them is returned.
+    pub fn getpriority(target: PriorityTarget) -> Result<c_int> {
+        let (which, who) = target.which_who();
+        // -1 is a valid nice value, so errors can only be told by errno
+        Errno::clear();
+        let res = unsafe { libc::getpriority(which as _, who) };
+        match Errno::last() {
+            Errno::UnknownErrno => Ok(res),
+            errno => Err(errno),
+        }
+    }
+
+    /// Set the nice value of a process, process group or user
+    /// ([`setpriority(2)`](https://man7.org/linux/man-pages/man2/setpriority.2.html)).
+    pub fn setpriority(target: PriorityTarget, prio: c_int) -> Result<()> {
+        let (which, who) = target.which_who();
+        let res = unsafe { libc::setpriority(which as _, who, prio) };
+
+        Errno::result(res).map(drop)
+    }
+
     /// `clone` create a child process
     /// ([`clone(2)`](https://man7.org/linux/man-pages/man2/clone.2.html))
     ///
@@ -212,6 +749,320 @@ mod sched_linux_like {
         Errno::result(res).map(Pid::from_raw)
     }
 
+    // Flags from linux/sched.h that don't fit in a c_int, and so can only be
+    // used with clone3
+    #[cfg(target_os = "linux")]
+    const CLONE_CLEAR_SIGHAND: u64 = 0x1_0000_0000;
+    #[cfg(target_os = "linux")]
+    const CLONE_INTO_CGROUP: u64 = 0x2_0000_0000;
+
+    // `struct clone_args` from linux/sched.h, which libc does not export yet
+    #[cfg(target_os = "linux")]
+    #[repr(C)]
+    struct clone_args {
+        flags: u64,
+        pidfd: u64,
+        child_tid: u64,
+        parent_tid: u64,
+        exit_signal: u64,
+        stack: u64,
+        stack_size: u64,
+        tls: u64,
+        set_tid: u64,
+        set_tid_size: u64,
+        cgroup: u64,
+    }
+
+    /// Arguments for [`clone3`](fn.clone3.html).
+    ///
+    /// By default the child is created like with `fork`: it gets a copy of
+    /// the parent's memory and sends `SIGCHLD` to the parent when it exits.
+    #[cfg(target_os = "linux")]
+    #[derive(Debug)]
+    pub struct CloneArgs<'a> {
+        flags: u64,
+        pidfd: Option<&'a mut RawFd>,
+        exit_signal: Option<Signal>,
+        set_tid: &'a [Pid],
+        cgroup: Option<RawFd>,
+    }
+
+    #[cfg(target_os = "linux")]
+    impl<'a> CloneArgs<'a> {
+        /// Create arguments that give the same result as `fork`.
+        pub fn new() -> Self {
+            CloneArgs {
+                flags: 0,
+                pidfd: None,
+                exit_signal: Some(Signal::SIGCHLD),
+                set_tid: &[],
+                cgroup: None,
+            }
+        }
+
+        /// Add `flags` to the flags passed to `clone3`.
+        ///
+        /// Flags that make the child share its memory or stack with the
+        /// parent, like `CLONE_VM`, must not be used.
+        pub fn flags(mut self, flags: CloneFlags) -> Self {
+            self.flags |= flags.bits() as u32 as u64;
+            self
+        }
+
+        /// Store a PID file descriptor referring to the child in `pidfd`
+        /// (`CLONE_PIDFD`).
+        pub fn pidfd(mut self, pidfd: &'a mut RawFd) -> Self {
+            self.flags |= CloneFlags::CLONE_PIDFD.bits() as u32 as u64;
+            self.pidfd = Some(pidfd);
+            self
+        }
+
+        /// Place the child in the cgroup v2 directory opened as `cgroup`
+        /// (`CLONE_INTO_CGROUP`).
+        pub fn into_cgroup(mut self, cgroup: RawFd) -> Self {
+            self.flags |= CLONE_INTO_CGROUP;
+            self.cgroup = Some(cgroup);
+            self
+        }
+
+        /// Reset all signal handlers of the child to their default
+        /// (`CLONE_CLEAR_SIGHAND`).
+        pub fn clear_sighand(mut self) -> Self {
+            self.flags |= CLONE_CLEAR_SIGHAND;
+            self
+        }
+
+        /// Choose the PID of the child in each of the PID namespaces it is
+        /// part of, starting with the innermost one.
+        pub fn set_tid(mut self, set_tid: &'a [Pid]) -> Self {
+            self.set_tid = set_tid;
+            self
+        }
+
+        /// Signal sent to the parent when the child exits, if any.
+        pub fn exit_signal(mut self, signal: Option<Signal>) -> Self {
+            self.exit_signal = signal;
+            self
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    impl<'a> Default for CloneArgs<'a> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    /// Create a child process
+    /// ([`clone3(2)`](https://man7.org/linux/man-pages/man2/clone3.2.html))
+    ///
+    /// Like [`fork`](../unistd/fn.fork.html), this returns in both the
+    /// parent and the child, without needing a callback or a separate stack.
+    ///
+    /// # Safety
+    ///
+    /// The same restrictions apply to the child as with `fork`.  In addition,
+    /// the C library is not told about the new process, so the child should
+    /// limit itself to raw system calls such as `_exit` or `execve`.
+    #[cfg(target_os = "linux")]
+    pub unsafe fn clone3(args: CloneArgs) -> Result<ForkResult> {
+        let set_tid: Vec<libc::pid_t> = args.set_tid.iter()
+            .map(|pid| pid.as_raw())
+            .collect();
+        let mut cl_args = clone_args {
+            flags: args.flags,
+            pidfd: args.pidfd.map_or(0, |pidfd| pidfd as *mut RawFd as u64),
+            child_tid: 0,
+            parent_tid: 0,
+            exit_signal: args.exit_signal.map_or(0, |signal| signal.as_raw() as u64),
+            stack: 0,
+            stack_size: 0,
+            tls: 0,
+            // The kernel rejects a non-null set_tid with a zero size
+            set_tid: if set_tid.is_empty() { 0 } else { set_tid.as_ptr() as u64 },
+            set_tid_size: set_tid.len() as u64,
+            cgroup: args.cgroup.map_or(0, |cgroup| cgroup as u64),
+        };
+        let res = libc::syscall(libc::SYS_clone3,
+                                &mut cl_args as *mut clone_args,
+                                mem::size_of::<clone_args>());
+
+        Errno::result(res).map(|res| match res {
+            0 => ForkResult::Child,
+            res => ForkResult::Parent { child: Pid::from_raw(res as libc::pid_t) },
+        })
+    }
+
+    /// The kinds of namespaces a process can be in.
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub enum Namespace {
+        /// Cgroup root directory
+        Cgroup,
+        /// System V IPC and POSIX message queues
+        Ipc,
+        /// Mount points
+        Mnt,
+        /// Network devices, stacks and ports
+        Net,
+        /// Process IDs
+        Pid,
+        /// Boot and monotonic clocks
+        #[cfg(target_os = "linux")]
+        Time,
+        /// User and group IDs
+        User,
+        /// Hostname and NIS domain name
+        Uts,
+    }
+
+    impl Namespace {
+        /// The flag for this kind of namespace, as used by `unshare`,
+        /// `setns` and `clone`.
+        pub fn flag(self) -> CloneFlags {
+            match self {
+                Namespace::Cgroup => CloneFlags::CLONE_NEWCGROUP,
+                Namespace::Ipc => CloneFlags::CLONE_NEWIPC,
+                Namespace::Mnt => CloneFlags::CLONE_NEWNS,
+                Namespace::Net => CloneFlags::CLONE_NEWNET,
+                Namespace::Pid => CloneFlags::CLONE_NEWPID,
+                #[cfg(target_os = "linux")]
+                Namespace::Time => CloneFlags::CLONE_NEWTIME,
+                Namespace::User => CloneFlags::CLONE_NEWUSER,
+                Namespace::Uts => CloneFlags::CLONE_NEWUTS,
+            }
+        }
+
+        /// The name of this kind of namespace in `/proc/<pid>/ns`.
+        pub fn proc_name(self) -> &'static str {
+            match self {
+                Namespace::Cgroup => "cgroup",
+                Namespace::Ipc => "ipc",
+                Namespace::Mnt => "mnt",
+                Namespace::Net => "net",
+                Namespace::Pid => "pid",
+                #[cfg(target_os = "linux")]
+                Namespace::Time => "time",
+                Namespace::User => "user",
+                Namespace::Uts => "uts",
+            }
+        }
+
+        /// Open the namespace of this kind that the process `pid` is in.
+        pub fn open(self, pid: Pid) -> Result<NsFd> {
+            let path = format!("/proc/{}/ns/{}", pid, self.proc_name());
+            let fd = fcntl::open(path.as_str(), OFlag::O_RDONLY | OFlag::O_CLOEXEC,
+                                 Mode::empty())?;
+            Ok(NsFd(fd))
+        }
+
+        fn from_nstype(nstype: c_int) -> Result<Namespace> {
+            Ok(match nstype {
+                libc::CLONE_NEWCGROUP => Namespace::Cgroup,
+                libc::CLONE_NEWIPC => Namespace::Ipc,
+                libc::CLONE_NEWNS => Namespace::Mnt,
+                libc::CLONE_NEWNET => Namespace::Net,
+                libc::CLONE_NEWPID => Namespace::Pid,
+                #[cfg(target_os = "linux")]
+                t if t == CloneFlags::CLONE_NEWTIME.bits() => Namespace::Time,
+                libc::CLONE_NEWUSER => Namespace::User,
+                libc::CLONE_NEWUTS => Namespace::Uts,
+                _ => return Err(Errno::EINVAL),
+            })
+        }
+    }
+
+    // Namespace ioctls from linux/nsfs.h
+    mod nsfs {
+        use crate::ioctl_none;
+
+        ioctl_none!(ns_get_userns, 0xb7, 0x1);
+        ioctl_none!(ns_get_parent, 0xb7, 0x2);
+        ioctl_none!(ns_get_nstype, 0xb7, 0x3);
+    }
+
+    /// A file descriptor referring to a namespace, as returned by
+    /// [`Namespace::open`](enum.Namespace.html#method.open).
+    ///
+    /// It can be passed to [`setns`](fn.setns.html) to enter the namespace.
+    /// This type implements `Drop`, so the file descriptor is closed when it
+    /// goes out of scope.
+    #[derive(Debug, Eq, Hash, PartialEq)]
+    pub struct NsFd(RawFd);
+
+    impl NsFd {
+        /// The kind of the namespace.  Since Linux 4.11.
+        pub fn kind(&self) -> Result<Namespace> {
+            let nstype = unsafe { nsfs::ns_get_nstype(self.0) }?;
+            Namespace::from_nstype(nstype)
+        }
+
+        /// The inode number that identifies the namespace, as displayed by
+        /// `readlink /proc/<pid>/ns/<kind>`.
+        pub fn inode(&self) -> Result<libc::ino_t> {
+            fstat(self.0).map(|st| st.st_ino)
+        }
+
+        /// Whether both file descriptors refer to the same namespace.
+        pub fn is_same(&self, other: &NsFd) -> Result<bool> {
+            let (st, other) = (fstat(self.0)?, fstat(other.0)?);
+            Ok(st.st_dev == other.st_dev && st.st_ino == other.st_ino)
+        }
+
+        /// Open the user namespace that owns this namespace.  Since Linux
+        /// 4.9.
+        pub fn user_ns(&self) -> Result<NsFd> {
+            unsafe { nsfs::ns_get_userns(self.0) }.map(NsFd)
+        }
+
+        /// Open the parent of this PID or user namespace.  Since Linux 4.9.
+        pub fn parent(&self) -> Result<NsFd> {
+            unsafe { nsfs::ns_get_parent(self.0) }.map(NsFd)
+        }
+    }
+
+    impl AsRawFd for NsFd {
+        fn as_raw_fd(&self) -> RawFd {
+            self.0
+        }
+    }
+
+    impl FromRawFd for NsFd {
+        unsafe fn from_raw_fd(fd: RawFd) -> Self {
+            NsFd(fd)
+        }
+    }
+
+    impl IntoRawFd for NsFd {
+        fn into_raw_fd(self) -> RawFd {
+            let fd = self.0;
+            mem::forget(self);
+            fd
+        }
+    }
+
+    impl Drop for NsFd {
+        fn drop(&mut self) {
+            // The namespace outlives the descriptor as long as something else
+            // uses it, so there is nothing to flush; EBADF means a double close.
+            let e = unistd::close(self.0);
+            if e == Err(Errno::EBADF) {
+                panic!("Closing an invalid file descriptor!");
+            };
+        }
+    }
+
+    /// Enter several namespaces of another process at once
+    /// ([`setns(2)`](https://man7.org/linux/man-pages/man2/setns.2.html))
+    ///
+    /// `pidfd` is a PID file descriptor, as returned by `clone3` or
+    /// `pidfd_open`.  Either all of `namespaces` are entered, or none of them.
+    /// Since Linux 5.8.
+    pub fn setns_pidfd(pidfd: RawFd, namespaces: &[Namespace]) -> Result<()> {
+        let flags = namespaces.iter()
+            .fold(CloneFlags::empty(), |flags, ns| flags | ns.flag());
+        setns(pidfd, flags)
+    }
+
     pub fn unshare(flags: CloneFlags) -> Result<()> {
         let res = unsafe { libc::unshare(flags.bits()) };
 
@@ -223,6 +1074,190 @@ mod sched_linux_like {
 
         Errno::result(res).map(drop)
     }
+
+    /// A range of user IDs mapped between a user namespace and its parent,
+    /// as in `/proc/<pid>/uid_map`.
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    pub struct UidMap {
+        /// First user ID of the range inside the namespace.
+        pub inside: Uid,
+        /// First user ID of the range in the parent namespace.
+        pub outside: Uid,
+        /// Length of the range.
+        pub count: u32,
+    }
+
+    impl UidMap {
+        /// Map `count` user IDs starting at `outside` to IDs starting at
+        /// `inside`.
+        pub const fn new(inside: Uid, outside: Uid, count: u32) -> Self {
+            UidMap { inside, outside, count }
+        }
+
+        fn range(&self) -> (u32, u32, u32) {
+            (self.inside.as_raw(), self.outside.as_raw(), self.count)
+        }
+    }
+
+    /// A range of group IDs mapped between a user namespace and its parent,
+    /// as in `/proc/<pid>/gid_map`.
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    pub struct GidMap {
+        /// First group ID of the range inside the namespace.
+        pub inside: Gid,
+        /// First group ID of the range in the parent namespace.
+        pub outside: Gid,
+        /// Length of the range.
+        pub count: u32,
+    }
+
+    impl GidMap {
+        /// Map `count` group IDs starting at `outside` to IDs starting at
+        /// `inside`.
+        pub const fn new(inside: Gid, outside: Gid, count: u32) -> Self {
+            GidMap { inside, outside, count }
+        }
+
+        fn range(&self) -> (u32, u32, u32) {
+            (self.inside.as_raw(), self.outside.as_raw(), self.count)
+        }
+    }
+
+    // Check that a map is one the kernel accepts, and format it to be written
+    // in a single write(2).
+    fn format_id_map(ranges: &[(u32, u32, u32)]) -> Result<String> {
+        if ranges.is_empty() {
+            return Err(Errno::EINVAL);
+        }
+        let overlaps = |mut starts: Vec<(u64, u64)>| {
+            starts.sort_unstable();
+            starts.windows(2).any(|w| w[0].0 + w[0].1 > w[1].0)
+        };
+        let inside = ranges.iter().map(|r| (u64::from(r.0), u64::from(r.2))).collect();
+        let outside = ranges.iter().map(|r| (u64::from(r.1), u64::from(r.2))).collect();
+        if ranges.iter().any(|r| r.2 == 0 ||
+                             u64::from(r.0) + u64::from(r.2) > 1 << 32 ||
+                             u64::from(r.1) + u64::from(r.2) > 1 << 32) ||
+            overlaps(inside) || overlaps(outside)
+        {
+            return Err(Errno::EINVAL);
+        }
+        Ok(ranges.iter()
+            .map(|r| format!("{} {} {}\n", r.0, r.1, r.2))
+            .collect())
+    }
+
+    fn parse_id_map(data: &[u8]) -> Result<Vec<(u32, u32, u32)>> {
+        let data = std::str::from_utf8(data).map_err(|_| Errno::EINVAL)?;
+        data.lines()
+            .map(|line| {
+                let mut fields = line.split_whitespace().map(str::parse::<u32>);
+                match (fields.next(), fields.next(), fields.next()) {
+                    (Some(Ok(inside)), Some(Ok(outside)), Some(Ok(count))) => {
+                        Ok((inside, outside, count))
+                    },
+                    _ => Err(Errno::EINVAL),
+                }
+            })
+            .collect()
+    }
+
+    fn write_proc_file(pid: Pid, name: &str, data: &[u8]) -> Result<()> {
+        let path = format!("/proc/{}/{}", pid, name);
+        let fd = fcntl::open(path.as_str(), OFlag::O_WRONLY | OFlag::O_CLOEXEC,
+                             Mode::empty())?;
+        // These files must be written with a single write(2)
+        let res = unistd::write(fd, data).and_then(|n| {
+            if n == data.len() { Ok(()) } else { Err(Errno::EIO) }
+        });
+        unistd::close(fd)?;
+        res
+    }
+
+    fn read_proc_file(pid: Pid, name: &str) -> Result<Vec<u8>> {
+        let path = format!("/proc/{}/{}", pid, name);
+        let fd = fcntl::open(path.as_str(), OFlag::O_RDONLY | OFlag::O_CLOEXEC,
+                             Mode::empty())?;
+        let res = unistd::read_to_end(fd);
+        unistd::close(fd)?;
+        res
+    }
+
+    /// Set the user ID mapping of the user namespace of `pid`, by writing
+    /// `/proc/<pid>/uid_map`
+    /// ([`user_namespaces(7)`](https://man7.org/linux/man-pages/man7/user_namespaces.7.html)).
+    ///
+    /// The map can only be written once.  Ranges must not overlap, neither
+    /// inside nor outside of the namespace; this is checked before writing
+    /// and reported as `EINVAL`.
+    pub fn write_uid_map(pid: Pid, map: &[UidMap]) -> Result<()> {
+        let ranges: Vec<_> = map.iter().map(|m| m.range()).collect();
+        write_proc_file(pid, "uid_map", format_id_map(&ranges)?.as_bytes())
+    }
+
+    /// Set the group ID mapping of the user namespace of `pid`, by writing
+    /// `/proc/<pid>/gid_map`.
+    ///
+    /// Unprivileged processes must call [`deny_setgroups`](fn.deny_setgroups.html)
+    /// first.  Ranges are checked like for [`write_uid_map`](fn.write_uid_map.html).
+    pub fn write_gid_map(pid: Pid, map: &[GidMap]) -> Result<()> {
+        let ranges: Vec<_> = map.iter().map(|m| m.range()).collect();
+        write_proc_file(pid, "gid_map", format_id_map(&ranges)?.as_bytes())
+    }
+
+    /// Permanently forbid `setgroups` in the user namespace of `pid`, by
+    /// writing `deny` to `/proc/<pid>/setgroups`.  Since Linux 3.19.
+    pub fn deny_setgroups(pid: Pid) -> Result<()> {
+        write_proc_file(pid, "setgroups", b"deny")
+    }
+
+    /// Set up the user namespace of `pid`: forbid `setgroups`, then write its
+    /// user and group ID mappings, as an unprivileged process has to.
+    ///
+    /// Both maps are checked before anything is written.
+    ///
+    /// # Example
+    ///
+    /// Map the current user and group to root in a new user namespace:
+    ///
+    /// ```rust,no_run
+    /// use nix::sched::{set_id_maps, unshare, CloneFlags, GidMap, UidMap};
+    /// use nix::unistd::{getgid, getuid, Gid, Pid, Uid};
+    ///
+    /// let (uid, gid) = (getuid(), getgid());
+    /// unshare(CloneFlags::CLONE_NEWUSER).unwrap();
+    /// set_id_maps(Pid::this(),
+    ///             &[UidMap::new(Uid::from_raw(0), uid, 1)],
+    ///             &[GidMap::new(Gid::from_raw(0), gid, 1)]).unwrap();
+    /// ```
+    pub fn set_id_maps(pid: Pid, uid_map: &[UidMap], gid_map: &[GidMap]) -> Result<()> {
+        let uid_ranges: Vec<_> = uid_map.iter().map(|m| m.range()).collect();
+        let gid_ranges: Vec<_> = gid_map.iter().map(|m| m.range()).collect();
+        let uid_map = format_id_map(&uid_ranges)?;
+        let gid_map = format_id_map(&gid_ranges)?;
+
+        deny_setgroups(pid)?;
+        write_proc_file(pid, "uid_map", uid_map.as_bytes())?;
+        write_proc_file(pid, "gid_map", gid_map.as_bytes())
+    }
+
+    /// Read the user ID mapping of the user namespace of `pid`, as seen from
+    /// the user namespace of the calling process.
+    pub fn read_uid_map(pid: Pid) -> Result<Vec<UidMap>> {
+        let ranges = parse_id_map(&read_proc_file(pid, "uid_map")?)?;
+        Ok(ranges.into_iter()
+            .map(|r| UidMap::new(Uid::from_raw(r.0), Uid::from_raw(r.1), r.2))
+            .collect())
+    }
+
+    /// Read the group ID mapping of the user namespace of `pid`, as seen
+    /// from the user namespace of the calling process.
+    pub fn read_gid_map(pid: Pid) -> Result<Vec<GidMap>> {
+        let ranges = parse_id_map(&read_proc_file(pid, "gid_map")?)?;
+        Ok(ranges.into_iter()
+            .map(|r| GidMap::new(Gid::from_raw(r.0), Gid::from_raw(r.1), r.2))
+            .collect())
+    }
 }
 
 /// Explicitly yield the processor to other threads.
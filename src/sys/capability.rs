@@ -0,0 +1,523 @@
+//! Query and change the capabilities of threads
+//!
+//! See [`capabilities(7)`](https://man7.org/linux/man-pages/man7/capabilities.7.html)
+//! for the meaning of each capability and of the capability sets.
+use std::fmt;
+use std::str::FromStr;
+use crate::{Error, Result};
+use crate::errno::Errno;
+use crate::fcntl::{self, OFlag};
+use crate::sys::stat::Mode;
+use crate::unistd::{self, Pid};
+
+use libc::{self, c_int, c_ulong};
+
+/// A capability, as understood by the kernel.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Capability {
+    /// Make arbitrary changes to file UIDs and GIDs.
+    CAP_CHOWN = 0,
+    /// Bypass file read, write, and execute permission checks.
+    CAP_DAC_OVERRIDE = 1,
+    /// Bypass file read permission checks and directory read and execute
+    /// permission checks.
+    CAP_DAC_READ_SEARCH = 2,
+    /// Bypass permission checks on operations that normally require the file
+    /// owner ID to match the caller's.
+    CAP_FOWNER = 3,
+    /// Don't clear set-user-ID and set-group-ID bits when a file is modified.
+    CAP_FSETID = 4,
+    /// Bypass permission checks for sending signals.
+    CAP_KILL = 5,
+    /// Make arbitrary manipulations of process GIDs.
+    CAP_SETGID = 6,
+    /// Make arbitrary manipulations of process UIDs.
+    CAP_SETUID = 7,
+    /// Add capabilities from the bounding set to the inheritable set, and
+    /// drop capabilities from the bounding set.
+    CAP_SETPCAP = 8,
+    /// Set the `FS_APPEND_FL` and `FS_IMMUTABLE_FL` inode flags.
+    CAP_LINUX_IMMUTABLE = 9,
+    /// Bind sockets to ports below 1024.
+    CAP_NET_BIND_SERVICE = 10,
+    /// Unused.
+    CAP_NET_BROADCAST = 11,
+    /// Perform various network-related operations.
+    CAP_NET_ADMIN = 12,
+    /// Use RAW and PACKET sockets.
+    CAP_NET_RAW = 13,
+    /// Lock memory.
+    CAP_IPC_LOCK = 14,
+    /// Bypass permission checks for operations on System V IPC objects.
+    CAP_IPC_OWNER = 15,
+    /// Load and unload kernel modules.
+    CAP_SYS_MODULE = 16,
+    /// Perform I/O port operations.
+    CAP_SYS_RAWIO = 17,
+    /// Use `chroot(2)`.
+    CAP_SYS_CHROOT = 18,
+    /// Trace arbitrary processes using `ptrace(2)`.
+    CAP_SYS_PTRACE = 19,
+    /// Use `acct(2)`.
+    CAP_SYS_PACCT = 20,
+    /// Perform a range of system administration operations.
+    CAP_SYS_ADMIN = 21,
+    /// Use `reboot(2)` and `kexec_load(2)`.
+    CAP_SYS_BOOT = 22,
+    /// Raise process nice values and change scheduling policies.
+    CAP_SYS_NICE = 23,
+    /// Override resource limits.
+    CAP_SYS_RESOURCE = 24,
+    /// Set the system clock.
+    CAP_SYS_TIME = 25,
+    /// Configure virtual terminals.
+    CAP_SYS_TTY_CONFIG = 26,
+    /// Create special files using `mknod(2)`.
+    CAP_MKNOD = 27,
+    /// Establish leases on arbitrary files.
+    CAP_LEASE = 28,
+    /// Write records to the kernel auditing log.
+    CAP_AUDIT_WRITE = 29,
+    /// Configure the kernel auditing system.
+    CAP_AUDIT_CONTROL = 30,
+    /// Set arbitrary capabilities on a file.
+    CAP_SETFCAP = 31,
+    /// Override Mandatory Access Control.
+    CAP_MAC_OVERRIDE = 32,
+    /// Configure Mandatory Access Control.
+    CAP_MAC_ADMIN = 33,
+    /// Configure the kernel's syslog.
+    CAP_SYSLOG = 34,
+    /// Trigger something that will wake up the system.
+    CAP_WAKE_ALARM = 35,
+    /// Block system suspend.
+    CAP_BLOCK_SUSPEND = 36,
+    /// Read the kernel auditing log.
+    CAP_AUDIT_READ = 37,
+    /// Perform performance monitoring operations.  Since Linux 5.8.
+    CAP_PERFMON = 38,
+    /// Perform privileged BPF operations.  Since Linux 5.8.
+    CAP_BPF = 39,
+    /// Perform checkpoint and restore operations.  Since Linux 5.9.
+    CAP_CHECKPOINT_RESTORE = 40,
+}
+
+use self::Capability::*;
+
+const CAPABILITIES: [Capability; 41] = [
+    CAP_CHOWN,
+    CAP_DAC_OVERRIDE,
+    CAP_DAC_READ_SEARCH,
+    CAP_FOWNER,
+    CAP_FSETID,
+    CAP_KILL,
+    CAP_SETGID,
+    CAP_SETUID,
+    CAP_SETPCAP,
+    CAP_LINUX_IMMUTABLE,
+    CAP_NET_BIND_SERVICE,
+    CAP_NET_BROADCAST,
+    CAP_NET_ADMIN,
+    CAP_NET_RAW,
+    CAP_IPC_LOCK,
+    CAP_IPC_OWNER,
+    CAP_SYS_MODULE,
+    CAP_SYS_RAWIO,
+    CAP_SYS_CHROOT,
+    CAP_SYS_PTRACE,
+    CAP_SYS_PACCT,
+    CAP_SYS_ADMIN,
+    CAP_SYS_BOOT,
+    CAP_SYS_NICE,
+    CAP_SYS_RESOURCE,
+    CAP_SYS_TIME,
+    CAP_SYS_TTY_CONFIG,
+    CAP_MKNOD,
+    CAP_LEASE,
+    CAP_AUDIT_WRITE,
+    CAP_AUDIT_CONTROL,
+    CAP_SETFCAP,
+    CAP_MAC_OVERRIDE,
+    CAP_MAC_ADMIN,
+    CAP_SYSLOG,
+    CAP_WAKE_ALARM,
+    CAP_BLOCK_SUSPEND,
+    CAP_AUDIT_READ,
+    CAP_PERFMON,
+    CAP_BPF,
+    CAP_CHECKPOINT_RESTORE,
+];
+
+impl Capability {
+    /// Iterate over all the capabilities known to nix.
+    ///
+    /// The running kernel may not support all of them; see
+    /// [`cap_last_cap`](fn.cap_last_cap.html).
+    pub fn iterator() -> impl Iterator<Item = Capability> {
+        CAPABILITIES.iter().cloned()
+    }
+
+    /// The capability with the given number, if it is known to nix.
+    pub fn from_c_int(cap: c_int) -> Result<Capability> {
+        if cap >= 0 && (cap as usize) < CAPABILITIES.len() {
+            Ok(CAPABILITIES[cap as usize])
+        } else {
+            Err(Errno::EINVAL)
+        }
+    }
+
+    /// The name of the capability, like `"CAP_SYS_ADMIN"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CAP_CHOWN => "CAP_CHOWN",
+            CAP_DAC_OVERRIDE => "CAP_DAC_OVERRIDE",
+            CAP_DAC_READ_SEARCH => "CAP_DAC_READ_SEARCH",
+            CAP_FOWNER => "CAP_FOWNER",
+            CAP_FSETID => "CAP_FSETID",
+            CAP_KILL => "CAP_KILL",
+            CAP_SETGID => "CAP_SETGID",
+            CAP_SETUID => "CAP_SETUID",
+            CAP_SETPCAP => "CAP_SETPCAP",
+            CAP_LINUX_IMMUTABLE => "CAP_LINUX_IMMUTABLE",
+            CAP_NET_BIND_SERVICE => "CAP_NET_BIND_SERVICE",
+            CAP_NET_BROADCAST => "CAP_NET_BROADCAST",
+            CAP_NET_ADMIN => "CAP_NET_ADMIN",
+            CAP_NET_RAW => "CAP_NET_RAW",
+            CAP_IPC_LOCK => "CAP_IPC_LOCK",
+            CAP_IPC_OWNER => "CAP_IPC_OWNER",
+            CAP_SYS_MODULE => "CAP_SYS_MODULE",
+            CAP_SYS_RAWIO => "CAP_SYS_RAWIO",
+            CAP_SYS_CHROOT => "CAP_SYS_CHROOT",
+            CAP_SYS_PTRACE => "CAP_SYS_PTRACE",
+            CAP_SYS_PACCT => "CAP_SYS_PACCT",
+            CAP_SYS_ADMIN => "CAP_SYS_ADMIN",
+            CAP_SYS_BOOT => "CAP_SYS_BOOT",
+            CAP_SYS_NICE => "CAP_SYS_NICE",
+            CAP_SYS_RESOURCE => "CAP_SYS_RESOURCE",
+            CAP_SYS_TIME => "CAP_SYS_TIME",
+            CAP_SYS_TTY_CONFIG => "CAP_SYS_TTY_CONFIG",
+            CAP_MKNOD => "CAP_MKNOD",
+            CAP_LEASE => "CAP_LEASE",
+            CAP_AUDIT_WRITE => "CAP_AUDIT_WRITE",
+            CAP_AUDIT_CONTROL => "CAP_AUDIT_CONTROL",
+            CAP_SETFCAP => "CAP_SETFCAP",
+            CAP_MAC_OVERRIDE => "CAP_MAC_OVERRIDE",
+            CAP_MAC_ADMIN => "CAP_MAC_ADMIN",
+            CAP_SYSLOG => "CAP_SYSLOG",
+            CAP_WAKE_ALARM => "CAP_WAKE_ALARM",
+            CAP_BLOCK_SUSPEND => "CAP_BLOCK_SUSPEND",
+            CAP_AUDIT_READ => "CAP_AUDIT_READ",
+            CAP_PERFMON => "CAP_PERFMON",
+            CAP_BPF => "CAP_BPF",
+            CAP_CHECKPOINT_RESTORE => "CAP_CHECKPOINT_RESTORE",
+        }
+    }
+}
+
+impl AsRef<str> for Capability {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Capability {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Capability> {
+        Capability::iterator()
+            .find(|cap| cap.as_str().eq_ignore_ascii_case(s))
+            .ok_or(Errno::EINVAL)
+    }
+}
+
+/// A set of capabilities.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct CapSet(u64);
+
+impl CapSet {
+    /// Initialize a set that contains all the capabilities known to nix.
+    pub fn all() -> CapSet {
+        Capability::iterator().collect()
+    }
+
+    /// Initialize an empty set.
+    pub fn empty() -> CapSet {
+        CapSet(0)
+    }
+
+    /// Initialize a set from its raw representation, with bit `n` standing
+    /// for capability `n`.
+    pub fn from_bits(bits: u64) -> CapSet {
+        CapSet(bits)
+    }
+
+    /// The raw representation of the set.
+    pub fn bits(self) -> u64 {
+        self.0
+    }
+
+    /// Add the specified capability to the set.
+    pub fn add(&mut self, cap: Capability) {
+        self.0 |= 1 << cap as u32;
+    }
+
+    /// Remove all capabilities from the set.
+    pub fn clear(&mut self) {
+        self.0 = 0;
+    }
+
+    /// Remove the specified capability from the set.
+    pub fn remove(&mut self, cap: Capability) {
+        self.0 &= !(1 << cap as u32);
+    }
+
+    /// Return whether this set includes the specified capability.
+    pub fn contains(self, cap: Capability) -> bool {
+        self.0 & (1 << cap as u32) != 0
+    }
+
+    /// Return whether this set includes no capability.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Merge all of `other`'s capabilities into this set.
+    pub fn extend(&mut self, other: CapSet) {
+        self.0 |= other.0;
+    }
+
+    /// Iterate over the capabilities of the set known to nix.
+    pub fn iter(self) -> impl Iterator<Item = Capability> {
+        Capability::iterator().filter(move |&cap| self.contains(cap))
+    }
+}
+
+impl std::iter::FromIterator<Capability> for CapSet {
+    fn from_iter<I: IntoIterator<Item = Capability>>(iter: I) -> CapSet {
+        let mut set = CapSet::empty();
+        for cap in iter {
+            set.add(cap);
+        }
+        set
+    }
+}
+
+/// The effective, permitted and inheritable capability sets of a thread.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Capabilities {
+    /// Capabilities used for permission checks.
+    pub effective: CapSet,
+    /// Limiting superset of the effective capabilities.
+    pub permitted: CapSet,
+    /// Capabilities preserved across `execve(2)`.
+    pub inheritable: CapSet,
+}
+
+// libc does not export the capget(2) structures yet
+const _LINUX_CAPABILITY_VERSION_3: u32 = 0x2008_0522;
+const _LINUX_CAPABILITY_U32S_3: usize = 2;
+
+#[repr(C)]
+struct cap_user_header {
+    version: u32,
+    pid: c_int,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+struct cap_user_data {
+    effective: u32,
+    permitted: u32,
+    inheritable: u32,
+}
+
+fn join(low: u32, high: u32) -> CapSet {
+    CapSet::from_bits(u64::from(high) << 32 | u64::from(low))
+}
+
+/// Get the capability sets of the thread `pid`, or of the calling thread if
+/// `pid` is zero
+/// ([`capget(2)`](https://man7.org/linux/man-pages/man2/capget.2.html)).
+pub fn capget(pid: Pid) -> Result<Capabilities> {
+    let mut header = cap_user_header {
+        version: _LINUX_CAPABILITY_VERSION_3,
+        pid: pid.as_raw(),
+    };
+    let mut data = [cap_user_data::default(); _LINUX_CAPABILITY_U32S_3];
+    let res = unsafe {
+        libc::syscall(libc::SYS_capget, &mut header as *mut cap_user_header,
+                      data.as_mut_ptr())
+    };
+
+    Errno::result(res).map(|_| Capabilities {
+        effective: join(data[0].effective, data[1].effective),
+        permitted: join(data[0].permitted, data[1].permitted),
+        inheritable: join(data[0].inheritable, data[1].inheritable),
+    })
+}
+
+/// Set the capability sets of the thread `pid`
+/// ([`capset(2)`](https://man7.org/linux/man-pages/man2/capget.2.html)).
+///
+/// Only the calling thread can be changed, so `pid` must be zero or the
+/// caller's thread ID.  Capabilities can only be added to the permitted set
+/// by executing a program.
+pub fn capset(pid: Pid, caps: &Capabilities) -> Result<()> {
+    let mut header = cap_user_header {
+        version: _LINUX_CAPABILITY_VERSION_3,
+        pid: pid.as_raw(),
+    };
+    let split = |set: CapSet| (set.bits() as u32, (set.bits() >> 32) as u32);
+    let (effective, permitted, inheritable) =
+        (split(caps.effective), split(caps.permitted), split(caps.inheritable));
+    let data = [
+        cap_user_data {
+            effective: effective.0,
+            permitted: permitted.0,
+            inheritable: inheritable.0,
+        },
+        cap_user_data {
+            effective: effective.1,
+            permitted: permitted.1,
+            inheritable: inheritable.1,
+        },
+    ];
+    let res = unsafe {
+        libc::syscall(libc::SYS_capset, &mut header as *mut cap_user_header,
+                      data.as_ptr())
+    };
+
+    Errno::result(res).map(drop)
+}
+
+#[cfg(target_os = "linux")]
+use libc::{PR_CAPBSET_DROP, PR_CAPBSET_READ, PR_CAP_AMBIENT, PR_CAP_AMBIENT_CLEAR_ALL,
+           PR_CAP_AMBIENT_IS_SET, PR_CAP_AMBIENT_LOWER, PR_CAP_AMBIENT_RAISE,
+           PR_GET_SECUREBITS, PR_SET_SECUREBITS};
+
+// libc does not export the capability options of prctl(2) on Android yet
+#[cfg(target_os = "android")]
+const PR_CAPBSET_READ: c_int = 23;
+#[cfg(target_os = "android")]
+const PR_CAPBSET_DROP: c_int = 24;
+#[cfg(target_os = "android")]
+const PR_GET_SECUREBITS: c_int = 27;
+#[cfg(target_os = "android")]
+const PR_SET_SECUREBITS: c_int = 28;
+#[cfg(target_os = "android")]
+const PR_CAP_AMBIENT: c_int = 47;
+#[cfg(target_os = "android")]
+const PR_CAP_AMBIENT_IS_SET: c_int = 1;
+#[cfg(target_os = "android")]
+const PR_CAP_AMBIENT_RAISE: c_int = 2;
+#[cfg(target_os = "android")]
+const PR_CAP_AMBIENT_LOWER: c_int = 3;
+#[cfg(target_os = "android")]
+const PR_CAP_AMBIENT_CLEAR_ALL: c_int = 4;
+
+fn prctl(option: c_int, arg2: c_ulong, arg3: c_ulong) -> Result<c_int> {
+    let res = unsafe { libc::prctl(option, arg2, arg3, 0 as c_ulong, 0 as c_ulong) };
+
+    Errno::result(res)
+}
+
+/// Whether `cap` is in the ambient set of the calling thread.  Since Linux
+/// 4.3.
+pub fn ambient_is_set(cap: Capability) -> Result<bool> {
+    prctl(PR_CAP_AMBIENT, PR_CAP_AMBIENT_IS_SET as c_ulong, cap as c_ulong)
+        .map(|res| res == 1)
+}
+
+/// Add `cap` to the ambient set of the calling thread.  Since Linux 4.3.
+///
+/// The capability must already be both permitted and inheritable.
+pub fn ambient_raise(cap: Capability) -> Result<()> {
+    prctl(PR_CAP_AMBIENT, PR_CAP_AMBIENT_RAISE as c_ulong, cap as c_ulong)
+        .map(drop)
+}
+
+/// Remove `cap` from the ambient set of the calling thread.  Since Linux
+/// 4.3.
+pub fn ambient_lower(cap: Capability) -> Result<()> {
+    prctl(PR_CAP_AMBIENT, PR_CAP_AMBIENT_LOWER as c_ulong, cap as c_ulong)
+        .map(drop)
+}
+
+/// Empty the ambient set of the calling thread.  Since Linux 4.3.
+pub fn ambient_clear_all() -> Result<()> {
+    prctl(PR_CAP_AMBIENT, PR_CAP_AMBIENT_CLEAR_ALL as c_ulong, 0)
+        .map(drop)
+}
+
+/// Whether `cap` is in the bounding set of the calling thread.
+pub fn bounding_is_set(cap: Capability) -> Result<bool> {
+    prctl(PR_CAPBSET_READ, cap as c_ulong, 0).map(|res| res == 1)
+}
+
+/// Remove `cap` from the bounding set of the calling thread, which requires
+/// `CAP_SETPCAP`.  It can never be added back.
+pub fn bounding_drop(cap: Capability) -> Result<()> {
+    prctl(PR_CAPBSET_DROP, cap as c_ulong, 0).map(drop)
+}
+
+/// The number of the highest capability supported by the running kernel, as
+/// read from `/proc/sys/kernel/cap_last_cap`.
+///
+/// This may be higher than the last capability known to nix.
+pub fn cap_last_cap() -> Result<c_int> {
+    let fd = fcntl::open("/proc/sys/kernel/cap_last_cap",
+                         OFlag::O_RDONLY | OFlag::O_CLOEXEC, Mode::empty())?;
+    let mut buf = [0u8; 16];
+    let res = unistd::read(fd, &mut buf);
+    unistd::close(fd)?;
+    let len = res?;
+    std::str::from_utf8(&buf[..len]).ok()
+        .and_then(|s| s.trim().parse().ok())
+        .ok_or(Errno::EINVAL)
+}
+
+// libc does not export the securebits yet
+bitflags::bitflags! {
+    /// Flags controlling how capabilities are granted to and kept by UID 0
+    /// ([`capabilities(7)`](https://man7.org/linux/man-pages/man7/capabilities.7.html)).
+    ///
+    /// Each `*_LOCKED` flag prevents any further change to the flag it
+    /// follows.
+    pub struct SecureBits: c_int {
+        /// Don't grant capabilities when executing programs as UID 0, or
+        /// set-user-ID root programs.
+        const SECBIT_NOROOT = 1 << 0;
+        /// Lock `SECBIT_NOROOT`.
+        const SECBIT_NOROOT_LOCKED = 1 << 1;
+        /// Don't adjust capabilities when the UIDs change to or from 0.
+        const SECBIT_NO_SETUID_FIXUP = 1 << 2;
+        /// Lock `SECBIT_NO_SETUID_FIXUP`.
+        const SECBIT_NO_SETUID_FIXUP_LOCKED = 1 << 3;
+        /// Keep the permitted capabilities when all UIDs change from 0 to
+        /// nonzero values.  Cleared by `execve(2)`.
+        const SECBIT_KEEP_CAPS = 1 << 4;
+        /// Lock `SECBIT_KEEP_CAPS`.
+        const SECBIT_KEEP_CAPS_LOCKED = 1 << 5;
+        /// Forbid raising ambient capabilities.  Since Linux 4.3.
+        const SECBIT_NO_CAP_AMBIENT_RAISE = 1 << 6;
+        /// Lock `SECBIT_NO_CAP_AMBIENT_RAISE`.  Since Linux 4.3.
+        const SECBIT_NO_CAP_AMBIENT_RAISE_LOCKED = 1 << 7;
+    }
+}
+
+/// Get the securebits of the calling thread.
+pub fn get_securebits() -> Result<SecureBits> {
+    prctl(PR_GET_SECUREBITS, 0, 0).map(SecureBits::from_bits_truncate)
+}
+
+/// Set the securebits of the calling thread, which requires `CAP_SETPCAP`.
+pub fn set_securebits(bits: SecureBits) -> Result<()> {
+    prctl(PR_SET_SECUREBITS, bits.bits() as c_ulong, 0).map(drop)
+}
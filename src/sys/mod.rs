@@ -6,6 +6,9 @@
           target_os = "netbsd"))]
 pub mod aio;
 
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub mod capability;
+
 #[cfg(any(target_os = "android", target_os = "linux"))]
 pub mod epoll;
 
@@ -59,6 +62,20 @@ pub mod quota;
 #[cfg(any(target_os = "linux"))]
 pub mod reboot;
 
+#[cfg(all(target_os = "linux",
+          any(target_arch = "aarch64",
+              target_arch = "arm",
+              target_arch = "mips",
+              target_arch = "mips64",
+              target_arch = "powerpc",
+              target_arch = "powerpc64",
+              target_arch = "riscv64",
+              target_arch = "s390x",
+              target_arch = "sparc64",
+              target_arch = "x86",
+              target_arch = "x86_64")))]
+pub mod seccomp;
+
 #[cfg(not(target_os = "redox"))]
 pub mod select;
 
@@ -109,3 +126,6 @@ pub mod inotify;
 
 #[cfg(target_os = "linux")]
 pub mod timerfd;
+
+#[cfg(target_os = "linux")]
+pub mod timer;
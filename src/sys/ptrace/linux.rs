@@ -1,12 +1,19 @@
 //! For detailed description of the ptrace requests, consult `man ptrace`.
 
 use cfg_if::cfg_if;
-use std::{mem, ptr};
+use std::collections::HashSet;
+use std::ffi::CString;
+use std::fs::{File, OpenOptions};
+use std::os::unix::fs::FileExt;
+use std::{cmp, mem, ptr};
 use crate::Result;
 use crate::errno::Errno;
-use libc::{self, c_void, c_long, siginfo_t};
+use libc::{self, c_void, c_long, c_ulong, siginfo_t};
 use crate::unistd::Pid;
-use crate::sys::signal::Signal;
+use crate::sys::signal::{self, Signal};
+#[cfg(target_os = "linux")]
+use crate::sys::uio::{process_vm_readv, process_vm_writev, IoVec, RemoteIoVec};
+use crate::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
 
 pub type AddressType = *mut ::libc::c_void;
 
@@ -139,7 +146,9 @@ libc_enum!{
         PTRACE_EVENT_EXIT,
         /// STop triggered by a seccomp rule on a tracee.
         PTRACE_EVENT_SECCOMP,
-        // PTRACE_EVENT_STOP not provided by libc because it's defined in glibc 2.26
+        /// Stop induced by `PTRACE_INTERRUPT` or `PTRACE_LISTEN`, or a
+        /// group-stop, on a tracee attached with `PTRACE_SEIZE`.
+        PTRACE_EVENT_STOP,
     }
 }
 
@@ -213,6 +222,453 @@ pub fn setregs(pid: Pid, regs: user_regs_struct) -> Result<()> {
     Errno::result(res).map(drop)
 }
 
+/// A register set of a tracee, identified by its ELF note type.  Used with
+/// [`getregset`](fn.getregset.html) and [`setregset`](fn.setregset.html).
+// libc does not export these yet
+#[cfg(all(target_os = "linux", not(any(target_arch = "mips",
+                                       target_arch = "mips64"))))]
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[repr(i32)]
+pub enum RegisterSet {
+    /// General purpose registers
+    NT_PRSTATUS = 1,
+    /// Floating point registers
+    NT_PRFPREG = 2,
+    /// Extended processor state, as saved by `xsave`
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    NT_X86_XSTATE = 0x202,
+    /// VFP registers
+    #[cfg(target_arch = "arm")]
+    NT_ARM_VFP = 0x400,
+    /// Thread pointer
+    #[cfg(target_arch = "aarch64")]
+    NT_ARM_TLS = 0x401,
+    /// Hardware breakpoint registers
+    #[cfg(target_arch = "aarch64")]
+    NT_ARM_HW_BREAK = 0x402,
+    /// Hardware watchpoint registers
+    #[cfg(target_arch = "aarch64")]
+    NT_ARM_HW_WATCH = 0x403,
+    /// System call number
+    #[cfg(target_arch = "aarch64")]
+    NT_ARM_SYSTEM_CALL = 0x404,
+    /// Scalable Vector Extension registers
+    #[cfg(target_arch = "aarch64")]
+    NT_ARM_SVE = 0x405,
+}
+
+#[cfg(all(target_os = "linux", not(any(target_arch = "mips",
+                                       target_arch = "mips64"))))]
+unsafe fn regset_get(pid: Pid, set: RegisterSet, data: *mut c_void, len: usize)
+    -> Result<usize>
+{
+    let mut iov = libc::iovec { iov_base: data, iov_len: len };
+    let res = libc::ptrace(Request::PTRACE_GETREGSET as RequestType,
+                           libc::pid_t::from(pid),
+                           set as libc::c_int as usize as *mut c_void,
+                           &mut iov as *mut libc::iovec as *mut c_void);
+    Errno::result(res)?;
+    Ok(iov.iov_len)
+}
+
+#[cfg(all(target_os = "linux", not(any(target_arch = "mips",
+                                       target_arch = "mips64"))))]
+unsafe fn regset_set(pid: Pid, set: RegisterSet, data: *const c_void, len: usize)
+    -> Result<()>
+{
+    let mut iov = libc::iovec { iov_base: data as *mut c_void, iov_len: len };
+    let res = libc::ptrace(Request::PTRACE_SETREGSET as RequestType,
+                           libc::pid_t::from(pid),
+                           set as libc::c_int as usize as *mut c_void,
+                           &mut iov as *mut libc::iovec as *mut c_void);
+    Errno::result(res).map(drop)
+}
+
+/// Get a register set of the tracee, as with `ptrace(PTRACE_GETREGSET, ...)`
+///
+/// The registers are stored in `buf` in the layout of the kernel.  Returns
+/// their size, which is less than the size of `buf` if it is larger than
+/// needed.  If `buf` is too small, the register set is truncated.
+#[cfg(all(target_os = "linux", not(any(target_arch = "mips",
+                                       target_arch = "mips64"))))]
+pub fn getregset(pid: Pid, set: RegisterSet, buf: &mut [u8]) -> Result<usize> {
+    unsafe {
+        regset_get(pid, set, buf.as_mut_ptr() as *mut c_void, buf.len())
+    }
+}
+
+/// Set a register set of the tracee, as with `ptrace(PTRACE_SETREGSET, ...)`
+///
+/// `buf` holds the registers in the layout of the kernel, as returned by
+/// [`getregset`](fn.getregset.html).
+#[cfg(all(target_os = "linux", not(any(target_arch = "mips",
+                                       target_arch = "mips64"))))]
+pub fn setregset(pid: Pid, set: RegisterSet, buf: &[u8]) -> Result<()> {
+    unsafe {
+        regset_set(pid, set, buf.as_ptr() as *const c_void, buf.len())
+    }
+}
+
+/// The general purpose registers of an AArch64 tracee, as returned for
+/// `NT_PRSTATUS`.
+#[cfg(all(target_os = "linux", target_arch = "aarch64"))]
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct user_pt_regs {
+    pub regs: [u64; 31],
+    pub sp: u64,
+    pub pc: u64,
+    pub pstate: u64,
+}
+
+// libc does not export it yet
+#[cfg(all(target_os = "linux", target_arch = "arm"))]
+const PTRACE_SET_SYSCALL: RequestType = 23;
+
+/// The general purpose registers of an ARM tracee, as returned for
+/// `NT_PRSTATUS`: `r0` to `r15`, `cpsr` and `orig_r0`.
+#[cfg(all(target_os = "linux", target_arch = "arm"))]
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct user_regs {
+    pub uregs: [libc::c_ulong; 18],
+}
+
+/// The general purpose registers of a RISC-V tracee, as returned for
+/// `NT_PRSTATUS`: `pc`, followed by `x1` to `x31`.
+#[cfg(all(target_os = "linux", target_arch = "riscv64"))]
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct user_regs_struct {
+    pub regs: [u64; 32],
+}
+
+// The register that holds a value, for the accessors of Registers
+#[cfg(all(
+    target_os = "linux",
+    any(all(target_arch = "x86_64",
+            any(target_env = "gnu", target_env = "musl")),
+        all(target_arch = "x86", target_env = "gnu"),
+        target_arch = "aarch64",
+        target_arch = "arm",
+        target_arch = "riscv64")
+))]
+#[derive(Clone, Copy)]
+enum Reg {
+    InstructionPointer,
+    StackPointer,
+    SyscallNumber,
+    SyscallArg(usize),
+    ReturnValue,
+}
+
+cfg_if! {
+    if #[cfg(all(target_os = "linux", target_arch = "x86_64",
+                 any(target_env = "gnu", target_env = "musl")))] {
+        /// The general purpose registers, in the layout of the architecture
+        pub type RawRegisters = user_regs_struct;
+        type Word = u64;
+
+        fn word_to_u64(w: Word) -> u64 { w }
+        fn word_to_i64(w: Word) -> i64 { w as i64 }
+        fn word_from_u64(v: u64) -> Word { v }
+
+        fn reg_mut(regs: &mut Registers, reg: Reg) -> &mut Word {
+            let r = &mut regs.regs;
+            match reg {
+                Reg::InstructionPointer => &mut r.rip,
+                Reg::StackPointer => &mut r.rsp,
+                Reg::SyscallNumber => &mut r.orig_rax,
+                Reg::SyscallArg(0) => &mut r.rdi,
+                Reg::SyscallArg(1) => &mut r.rsi,
+                Reg::SyscallArg(2) => &mut r.rdx,
+                Reg::SyscallArg(3) => &mut r.r10,
+                Reg::SyscallArg(4) => &mut r.r8,
+                Reg::SyscallArg(5) => &mut r.r9,
+                Reg::SyscallArg(n) => panic!("Invalid system call argument {}", n),
+                Reg::ReturnValue => &mut r.rax,
+            }
+        }
+    } else if #[cfg(all(target_os = "linux", target_arch = "x86",
+                        target_env = "gnu"))] {
+        /// The general purpose registers, in the layout of the architecture
+        pub type RawRegisters = user_regs_struct;
+        type Word = c_long;
+
+        fn word_to_u64(w: Word) -> u64 { w as u32 as u64 }
+        fn word_to_i64(w: Word) -> i64 { w as i64 }
+        fn word_from_u64(v: u64) -> Word { v as u32 as c_long }
+
+        fn reg_mut(regs: &mut Registers, reg: Reg) -> &mut Word {
+            let r = &mut regs.regs;
+            match reg {
+                Reg::InstructionPointer => &mut r.eip,
+                Reg::StackPointer => &mut r.esp,
+                Reg::SyscallNumber => &mut r.orig_eax,
+                Reg::SyscallArg(0) => &mut r.ebx,
+                Reg::SyscallArg(1) => &mut r.ecx,
+                Reg::SyscallArg(2) => &mut r.edx,
+                Reg::SyscallArg(3) => &mut r.esi,
+                Reg::SyscallArg(4) => &mut r.edi,
+                Reg::SyscallArg(5) => &mut r.ebp,
+                Reg::SyscallArg(n) => panic!("Invalid system call argument {}", n),
+                Reg::ReturnValue => &mut r.eax,
+            }
+        }
+    } else if #[cfg(all(target_os = "linux", target_arch = "aarch64"))] {
+        /// The general purpose registers, in the layout of the architecture
+        pub type RawRegisters = user_pt_regs;
+        type Word = u64;
+
+        fn word_to_u64(w: Word) -> u64 { w }
+        fn word_to_i64(w: Word) -> i64 { w as i64 }
+        fn word_from_u64(v: u64) -> Word { v }
+
+        fn reg_mut(regs: &mut Registers, reg: Reg) -> &mut Word {
+            match reg {
+                Reg::InstructionPointer => &mut regs.regs.pc,
+                Reg::StackPointer => &mut regs.regs.sp,
+                // x8 is only read when entering the system call, and
+                // changing it afterwards has no effect.
+                Reg::SyscallNumber => &mut regs.syscallno,
+                Reg::SyscallArg(n) if n < 6 => &mut regs.regs.regs[n],
+                Reg::SyscallArg(n) => panic!("Invalid system call argument {}", n),
+                Reg::ReturnValue => &mut regs.regs.regs[0],
+            }
+        }
+    } else if #[cfg(all(target_os = "linux", target_arch = "arm"))] {
+        /// The general purpose registers, in the layout of the architecture
+        pub type RawRegisters = user_regs;
+        type Word = libc::c_ulong;
+
+        fn word_to_u64(w: Word) -> u64 { w as u64 }
+        fn word_to_i64(w: Word) -> i64 { w as i32 as i64 }
+        fn word_from_u64(v: u64) -> Word { v as Word }
+
+        fn reg_mut(regs: &mut Registers, reg: Reg) -> &mut Word {
+            let r = &mut regs.regs.uregs;
+            match reg {
+                Reg::InstructionPointer => &mut r[15],
+                Reg::StackPointer => &mut r[13],
+                Reg::SyscallNumber => &mut r[7],
+                Reg::SyscallArg(n) if n < 6 => &mut r[n],
+                Reg::SyscallArg(n) => panic!("Invalid system call argument {}", n),
+                Reg::ReturnValue => &mut r[0],
+            }
+        }
+    } else if #[cfg(all(target_os = "linux", target_arch = "riscv64"))] {
+        /// The general purpose registers, in the layout of the architecture
+        pub type RawRegisters = user_regs_struct;
+        type Word = u64;
+
+        fn word_to_u64(w: Word) -> u64 { w }
+        fn word_to_i64(w: Word) -> i64 { w as i64 }
+        fn word_from_u64(v: u64) -> Word { v }
+
+        fn reg_mut(regs: &mut Registers, reg: Reg) -> &mut Word {
+            let r = &mut regs.regs.regs;
+            match reg {
+                Reg::InstructionPointer => &mut r[0],
+                Reg::StackPointer => &mut r[2],
+                // a7
+                Reg::SyscallNumber => &mut r[17],
+                // a0 to a5
+                Reg::SyscallArg(n) if n < 6 => &mut r[10 + n],
+                Reg::SyscallArg(n) => panic!("Invalid system call argument {}", n),
+                Reg::ReturnValue => &mut r[10],
+            }
+        }
+    }
+}
+
+/// The general purpose registers of a tracee, with accessors that work the
+/// same on every architecture.
+///
+/// The registers are read and written with `PTRACE_GETREGSET` and
+/// `PTRACE_SETREGSET`.  The system call number and arguments are only
+/// meaningful at a system call entry stop, and the return value at a system
+/// call exit stop.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use nix::sys::ptrace::{self, Registers};
+/// # use nix::unistd::Pid;
+/// # let pid = Pid::from_raw(1);
+/// // At a system call entry stop, replace the system call by getpid
+/// let mut regs = Registers::get(pid).unwrap();
+/// println!("syscall {} at {:#x}", regs.syscall_number(), regs.instruction_pointer());
+/// regs.set_syscall_number(libc::SYS_getpid as i64);
+/// regs.set(pid).unwrap();
+/// ```
+#[cfg(all(
+    target_os = "linux",
+    any(all(target_arch = "x86_64",
+            any(target_env = "gnu", target_env = "musl")),
+        all(target_arch = "x86", target_env = "gnu"),
+        target_arch = "aarch64",
+        target_arch = "arm",
+        target_arch = "riscv64")
+))]
+#[derive(Clone, Copy, Debug)]
+pub struct Registers {
+    regs: RawRegisters,
+    // AArch64 keeps the system call number apart from the registers
+    #[cfg(target_arch = "aarch64")]
+    syscallno: u64,
+}
+
+#[cfg(all(
+    target_os = "linux",
+    any(all(target_arch = "x86_64",
+            any(target_env = "gnu", target_env = "musl")),
+        all(target_arch = "x86", target_env = "gnu"),
+        target_arch = "aarch64",
+        target_arch = "arm",
+        target_arch = "riscv64")
+))]
+impl Registers {
+    /// Reads the general purpose registers of the tracee.
+    ///
+    /// Fails with `EINVAL` if the tracee runs with a different architecture
+    /// than the tracer, like a 32-bit tracee of a 64-bit tracer.
+    pub fn get(pid: Pid) -> Result<Registers> {
+        let mut regs = mem::MaybeUninit::<RawRegisters>::zeroed();
+        let len = unsafe {
+            regset_get(pid, RegisterSet::NT_PRSTATUS,
+                       regs.as_mut_ptr() as *mut c_void,
+                       mem::size_of::<RawRegisters>())?
+        };
+        if len != mem::size_of::<RawRegisters>() {
+            return Err(Errno::EINVAL);
+        }
+        #[cfg(target_arch = "aarch64")]
+        let syscallno = {
+            let mut nr: libc::c_int = 0;
+            unsafe {
+                regset_get(pid, RegisterSet::NT_ARM_SYSTEM_CALL,
+                           &mut nr as *mut libc::c_int as *mut c_void,
+                           mem::size_of::<libc::c_int>())?;
+            }
+            nr as i64 as u64
+        };
+
+        Ok(Registers {
+            regs: unsafe { regs.assume_init() },
+            #[cfg(target_arch = "aarch64")]
+            syscallno,
+        })
+    }
+
+    /// Writes the registers back to the tracee.
+    ///
+    /// On ARM, the kernel reads the system call number from `r7` only when
+    /// the system call is entered, so the number is also written with
+    /// `PTRACE_SET_SYSCALL`.
+    pub fn set(&self, pid: Pid) -> Result<()> {
+        unsafe {
+            regset_set(pid, RegisterSet::NT_PRSTATUS,
+                       &self.regs as *const RawRegisters as *const c_void,
+                       mem::size_of::<RawRegisters>())?;
+        }
+        #[cfg(target_arch = "aarch64")]
+        unsafe {
+            let nr = self.syscallno as libc::c_int;
+            regset_set(pid, RegisterSet::NT_ARM_SYSTEM_CALL,
+                       &nr as *const libc::c_int as *const c_void,
+                       mem::size_of::<libc::c_int>())?;
+        }
+        #[cfg(target_arch = "arm")]
+        Errno::result(unsafe {
+            libc::ptrace(PTRACE_SET_SYSCALL,
+                         libc::pid_t::from(pid),
+                         ptr::null_mut::<c_void>(),
+                         self.regs.uregs[7] as *mut c_void)
+        })?;
+        Ok(())
+    }
+
+    fn reg(&self, reg: Reg) -> Word {
+        let mut regs = *self;
+        *reg_mut(&mut regs, reg)
+    }
+
+    /// The address of the next instruction.
+    pub fn instruction_pointer(&self) -> u64 {
+        word_to_u64(self.reg(Reg::InstructionPointer))
+    }
+
+    /// Changes the address of the next instruction.
+    pub fn set_instruction_pointer(&mut self, value: u64) {
+        *reg_mut(self, Reg::InstructionPointer) = word_from_u64(value);
+    }
+
+    /// The top of the stack.
+    pub fn stack_pointer(&self) -> u64 {
+        word_to_u64(self.reg(Reg::StackPointer))
+    }
+
+    /// Changes the top of the stack.
+    pub fn set_stack_pointer(&mut self, value: u64) {
+        *reg_mut(self, Reg::StackPointer) = word_from_u64(value);
+    }
+
+    /// The number of the system call, like `libc::SYS_read`.
+    pub fn syscall_number(&self) -> i64 {
+        word_to_i64(self.reg(Reg::SyscallNumber))
+    }
+
+    /// Changes the system call about to be made, at a system call entry
+    /// stop.  Setting it to -1 skips the system call.
+    pub fn set_syscall_number(&mut self, value: i64) {
+        *reg_mut(self, Reg::SyscallNumber) = word_from_u64(value as u64);
+    }
+
+    /// The argument `n` of the system call, from 0 to 5.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is greater than 5.
+    pub fn syscall_arg(&self, n: usize) -> u64 {
+        word_to_u64(self.reg(Reg::SyscallArg(n)))
+    }
+
+    /// Changes the argument `n` of the system call, from 0 to 5.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is greater than 5.
+    pub fn set_syscall_arg(&mut self, n: usize, value: u64) {
+        *reg_mut(self, Reg::SyscallArg(n)) = word_from_u64(value);
+    }
+
+    /// The value returned by the system call.  Errors are returned as
+    /// negated `errno` values.
+    pub fn return_value(&self) -> i64 {
+        word_to_i64(self.reg(Reg::ReturnValue))
+    }
+
+    /// Changes the value returned by the system call, at a system call exit
+    /// stop.  Errors are returned as negated `errno` values.
+    pub fn set_return_value(&mut self, value: i64) {
+        *reg_mut(self, Reg::ReturnValue) = word_from_u64(value as u64);
+    }
+
+    /// The registers, in the layout of the architecture.
+    pub fn raw(&self) -> &RawRegisters {
+        &self.regs
+    }
+
+    /// The registers, in the layout of the architecture, for modification.
+    pub fn raw_mut(&mut self) -> &mut RawRegisters {
+        &mut self.regs
+    }
+}
+
 /// Function for ptrace requests that return values from the data field.
 /// Some ptrace get requests populate structs or larger elements than `c_long`
 /// and therefore use the data field to return values. This function handles these
@@ -269,6 +725,107 @@ pub fn setsiginfo(pid: Pid, sig: &siginfo_t) -> Result<()> {
     }
 }
 
+#[cfg(all(target_os = "linux", not(any(target_arch = "mips", target_arch = "mips64"))))]
+#[repr(C)]
+struct ptrace_peeksiginfo_args {
+    off: u64,
+    flags: u32,
+    nr: i32,
+}
+
+// libc does not export these on every target yet
+#[cfg(all(target_os = "linux", not(any(target_arch = "mips", target_arch = "mips64"))))]
+const PTRACE_PEEKSIGINFO_SHARED: u32 = 1;
+const PTRACE_SECCOMP_GET_FILTER: RequestType = 0x420c;
+const PTRACE_SECCOMP_GET_METADATA: RequestType = 0x420d;
+
+/// Get the pending signals of a tracee without dequeuing them, as with
+/// `ptrace(PTRACE_PEEKSIGINFO, ...)`
+///
+/// Returns the signals from the `offset`th onwards, in queue order.  With
+/// `shared`, they are taken from the queue of the whole process, and
+/// otherwise from the queue of the thread `pid`.
+#[cfg(all(target_os = "linux", not(any(target_arch = "mips", target_arch = "mips64"))))]
+pub fn peeksiginfo(pid: Pid, offset: u64, shared: bool) -> Result<Vec<siginfo_t>> {
+    const BATCH: usize = 16;
+    let mut siginfos = Vec::<siginfo_t>::new();
+    loop {
+        siginfos.reserve(BATCH);
+        let args = ptrace_peeksiginfo_args {
+            off: offset + siginfos.len() as u64,
+            flags: if shared { PTRACE_PEEKSIGINFO_SHARED } else { 0 },
+            nr: BATCH as i32,
+        };
+        let len = siginfos.len();
+        let res = unsafe {
+            libc::ptrace(Request::PTRACE_PEEKSIGINFO as RequestType,
+                         libc::pid_t::from(pid),
+                         &args as *const ptrace_peeksiginfo_args,
+                         siginfos.as_mut_ptr().add(len))
+        };
+        let n = Errno::result(res)? as usize;
+        if n == 0 {
+            return Ok(siginfos);
+        }
+        // The kernel initialized the first n entries of the spare capacity
+        unsafe { siginfos.set_len(len + n) };
+    }
+}
+
+/// Get a seccomp filter of a tracee, as with
+/// `ptrace(PTRACE_SECCOMP_GET_FILTER, ...)`
+///
+/// `index` counts the filters of the tracee from the most recently
+/// installed one, which is 0.  Fails with `ENOENT` past the oldest filter,
+/// and with `EMEDIUMTYPE` for a filter that is not a classic BPF program.
+/// This requires `CAP_SYS_ADMIN`.
+pub fn seccomp_get_filter(pid: Pid, index: c_ulong) -> Result<Vec<libc::sock_filter>> {
+    let request = |program: *mut libc::sock_filter| {
+        Errno::result(unsafe {
+            libc::ptrace(PTRACE_SECCOMP_GET_FILTER,
+                         libc::pid_t::from(pid),
+                         index as *mut c_void,
+                         program as *mut c_void)
+        }).map(|len| len as usize)
+    };
+
+    let len = request(ptr::null_mut())?;
+    let mut program = Vec::with_capacity(len);
+    let len = request(program.as_mut_ptr())?;
+    // The filters of a stopped tracee can't change
+    unsafe { program.set_len(len) };
+    Ok(program)
+}
+
+/// Metadata of a seccomp filter, returned by
+/// [`seccomp_get_metadata`](fn.seccomp_get_metadata.html).
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SeccompMetadata {
+    /// The index of the filter.
+    pub filter_off: u64,
+    /// The flags the filter was installed with, among which the kernel only
+    /// reports `SECCOMP_FILTER_FLAG_LOG`.
+    pub flags: u64,
+}
+
+/// Get the metadata of a seccomp filter of a tracee, as with
+/// `ptrace(PTRACE_SECCOMP_GET_METADATA, ...)`
+///
+/// `index` is interpreted as for
+/// [`seccomp_get_filter`](fn.seccomp_get_filter.html).  This requires
+/// `CAP_SYS_ADMIN`.
+pub fn seccomp_get_metadata(pid: Pid, index: u64) -> Result<SeccompMetadata> {
+    let mut metadata = SeccompMetadata { filter_off: index, flags: 0 };
+    let res = unsafe {
+        libc::ptrace(PTRACE_SECCOMP_GET_METADATA,
+                     libc::pid_t::from(pid),
+                     mem::size_of::<SeccompMetadata>() as *mut c_void,
+                     &mut metadata as *mut SeccompMetadata as *mut c_void)
+    };
+    Errno::result(res).map(|_| metadata)
+}
+
 /// Sets the process as traceable, as with `ptrace(PTRACE_TRACEME, ...)`
 ///
 /// Indicates that this process is to be traced by its parent.
@@ -290,7 +847,7 @@ pub fn traceme() -> Result<()> {
 /// optionally delivering a signal specified by `sig`.
 pub fn syscall<T: Into<Option<Signal>>>(pid: Pid, sig: T) -> Result<()> {
     let data = match sig.into() {
-        Some(s) => s as i32 as *mut c_void,
+        Some(s) => s.as_raw() as *mut c_void,
         None => ptr::null_mut(),
     };
     unsafe {
@@ -311,7 +868,7 @@ pub fn syscall<T: Into<Option<Signal>>>(pid: Pid, sig: T) -> Result<()> {
 #[cfg(all(target_os = "linux", target_env = "gnu", any(target_arch = "x86", target_arch = "x86_64")))]
 pub fn sysemu<T: Into<Option<Signal>>>(pid: Pid, sig: T) -> Result<()> {
     let data = match sig.into() {
-        Some(s) => s as i32 as *mut c_void,
+        Some(s) => s.as_raw() as *mut c_void,
         None => ptr::null_mut(),
     };
     unsafe {
@@ -349,13 +906,42 @@ pub fn seize(pid: Pid, options: Options) -> Result<()> {
     }
 }
 
+/// Stop a tracee, as with `ptrace(PTRACE_INTERRUPT, ...)`
+///
+/// The tracee must have been attached with [`seize`](fn.seize.html).  It
+/// reports a `PTRACE_EVENT_STOP` with `SIGTRAP` to `waitpid`, unless it
+/// stops for another reason first, such as a system call or a signal.
+#[cfg(all(target_os = "linux", not(any(target_arch = "mips", target_arch = "mips64"))))]
+pub fn interrupt(pid: Pid) -> Result<()> {
+    unsafe {
+        ptrace_other(Request::PTRACE_INTERRUPT, pid, ptr::null_mut(), ptr::null_mut())
+            .map(drop) // ignore the useless return value
+    }
+}
+
+/// Restart a tracee in a group-stop without running it, as with
+/// `ptrace(PTRACE_LISTEN, ...)`
+///
+/// The tracee must have been attached with [`seize`](fn.seize.html).  It
+/// stays stopped, but reports a new `PTRACE_EVENT_STOP` to `waitpid` when it
+/// is woken up, for instance by `SIGCONT`.  Unlike resuming it with
+/// [`cont`](fn.cont.html), this lets the job control of the tracee work as
+/// if it was not traced.
+#[cfg(all(target_os = "linux", not(any(target_arch = "mips", target_arch = "mips64"))))]
+pub fn listen(pid: Pid) -> Result<()> {
+    unsafe {
+        ptrace_other(Request::PTRACE_LISTEN, pid, ptr::null_mut(), ptr::null_mut())
+            .map(drop) // ignore the useless return value
+    }
+}
+
 /// Detaches the current running process, as with `ptrace(PTRACE_DETACH, ...)`
 ///
 /// Detaches from the process specified by `pid` allowing it to run freely, optionally delivering a
 /// signal specified by `sig`.
 pub fn detach<T: Into<Option<Signal>>>(pid: Pid, sig: T) -> Result<()> {
     let data = match sig.into() {
-        Some(s) => s as i32 as *mut c_void,
+        Some(s) => s.as_raw() as *mut c_void,
         None => ptr::null_mut(),
     };
     unsafe {
@@ -374,7 +960,7 @@ pub fn detach<T: Into<Option<Signal>>>(pid: Pid, sig: T) -> Result<()> {
 /// delivering a signal specified by `sig`.
 pub fn cont<T: Into<Option<Signal>>>(pid: Pid, sig: T) -> Result<()> {
     let data = match sig.into() {
-        Some(s) => s as i32 as *mut c_void,
+        Some(s) => s.as_raw() as *mut c_void,
         None => ptr::null_mut(),
     };
     unsafe {
@@ -417,7 +1003,7 @@ pub fn kill(pid: Pid) -> Result<()> {
 /// ```
 pub fn step<T: Into<Option<Signal>>>(pid: Pid, sig: T) -> Result<()> {
     let data = match sig.into() {
-        Some(s) => s as i32 as *mut c_void,
+        Some(s) => s.as_raw() as *mut c_void,
         None => ptr::null_mut(),
     };
     unsafe {
@@ -434,7 +1020,7 @@ pub fn step<T: Into<Option<Signal>>>(pid: Pid, sig: T) -> Result<()> {
 #[cfg(all(target_os = "linux", target_env = "gnu", any(target_arch = "x86", target_arch = "x86_64")))]
 pub fn sysemu_step<T: Into<Option<Signal>>>(pid: Pid, sig: T) -> Result<()> {
     let data = match sig.into() {
-        Some(s) => s as i32 as *mut c_void,
+        Some(s) => s.as_raw() as *mut c_void,
         None => ptr::null_mut(),
     };
     unsafe {
@@ -466,3 +1052,573 @@ pub unsafe fn write(
 {
     ptrace_other(Request::PTRACE_POKEDATA, pid, addr, data).map(drop)
 }
+
+/// Reads `buf.len()` bytes from the memory of `pid` at `addr`.
+///
+/// Unlike [`read`](fn.read.html), this moves any number of bytes at any
+/// alignment.  It uses `process_vm_readv` where available, and falls back to
+/// `/proc/<pid>/mem` and then to `PTRACE_PEEKDATA` for the bytes it could not
+/// read.  The error of the last method is returned if all of them fail.
+///
+/// Only `PTRACE_PEEKDATA` requires `pid` to be a stopped tracee; the other
+/// methods require the permission to trace it.
+pub fn read_bytes(pid: Pid, addr: AddressType, buf: &mut [u8]) -> Result<()> {
+    let mut done = 0;
+
+    #[cfg(target_os = "linux")]
+    {
+        done += read_vm(pid, addr as usize, buf);
+        if done == buf.len() {
+            return Ok(());
+        }
+    }
+
+    if let Ok(file) = File::open(format!("/proc/{}/mem", pid)) {
+        while done < buf.len() {
+            match file.read_at(&mut buf[done..], (addr as usize + done) as u64) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => done += n,
+            }
+        }
+    }
+
+    read_words(pid, addr as usize + done, &mut buf[done..])
+}
+
+/// Writes `buf` to the memory of `pid` at `addr`.
+///
+/// Unlike [`write`](fn.write.html), this moves any number of bytes at any
+/// alignment.  It uses `process_vm_writev` where available, and falls back to
+/// `/proc/<pid>/mem` and then to `PTRACE_POKEDATA` for the bytes it could not
+/// write.  The fallbacks can write to read-only pages, such as the code of
+/// the tracee, which is how breakpoints are usually inserted.  The error of
+/// the last method is returned if all of them fail.
+///
+/// Only `PTRACE_POKEDATA` requires `pid` to be a stopped tracee; the other
+/// methods require the permission to trace it.
+///
+/// # Safety
+///
+/// Like [`write`](fn.write.html), this writes arbitrary memory of `pid`,
+/// which may be the calling process itself.  The caller must make sure that
+/// the write can't break the memory safety of that process.
+pub unsafe fn write_bytes(pid: Pid, addr: AddressType, buf: &[u8]) -> Result<()> {
+    let mut done = 0;
+
+    #[cfg(target_os = "linux")]
+    {
+        done += write_vm(pid, addr as usize, buf);
+        if done == buf.len() {
+            return Ok(());
+        }
+    }
+
+    let mem = OpenOptions::new().write(true).open(format!("/proc/{}/mem", pid));
+    if let Ok(file) = mem {
+        while done < buf.len() {
+            match file.write_at(&buf[done..], (addr as usize + done) as u64) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => done += n,
+            }
+        }
+    }
+
+    write_words(pid, addr as usize + done, &buf[done..])
+}
+
+/// Reads a NUL-terminated string from the memory of `pid` at `addr`, as with
+/// [`read_bytes`](fn.read_bytes.html).
+pub fn read_cstring(pid: Pid, addr: AddressType) -> Result<CString> {
+    let mut bytes = Vec::new();
+    let mut addr = addr as usize;
+    loop {
+        // The string may end right before an unmapped page
+        let len = MIN_PAGE_SIZE - addr % MIN_PAGE_SIZE;
+        let start = bytes.len();
+        bytes.resize(start + len, 0);
+        read_bytes(pid, addr as AddressType, &mut bytes[start..])?;
+        if let Some(nul) = bytes[start..].iter().position(|&b| b == 0) {
+            bytes.truncate(start + nul);
+            // The bytes contain no NUL now
+            return Ok(unsafe { CString::from_vec_unchecked(bytes) });
+        }
+        addr += len;
+    }
+}
+
+/// Reads a NULL-terminated array of pointers from the memory of `pid` at
+/// `addr`, as with [`read_bytes`](fn.read_bytes.html).  The terminating NULL
+/// is not included.
+///
+/// The pointers have the size of the tracer's, so this can't read the arrays
+/// of a 32-bit tracee from a 64-bit tracer.
+///
+/// # Examples
+///
+/// Read the arguments of an `execve` call, given its `argv` argument.
+/// ```
+/// # use nix::Result;
+/// # use nix::sys::ptrace::{self, AddressType};
+/// # use nix::unistd::Pid;
+/// # use std::ffi::CString;
+/// fn read_argv(pid: Pid, argv: AddressType) -> Result<Vec<CString>> {
+///     ptrace::read_pointers(pid, argv)?
+///         .into_iter()
+///         .map(|arg| ptrace::read_cstring(pid, arg))
+///         .collect()
+/// }
+/// ```
+pub fn read_pointers(pid: Pid, addr: AddressType) -> Result<Vec<AddressType>> {
+    const SIZE: usize = mem::size_of::<usize>();
+    let mut pointers = Vec::new();
+    let mut bytes = [0u8; MIN_PAGE_SIZE];
+    let mut addr = addr as usize;
+    loop {
+        // The array may end right before an unmapped page
+        let len = cmp::max((MIN_PAGE_SIZE - addr % MIN_PAGE_SIZE) / SIZE * SIZE, SIZE);
+        read_bytes(pid, addr as AddressType, &mut bytes[..len])?;
+        for chunk in bytes[..len].chunks(SIZE) {
+            let mut pointer = [0u8; SIZE];
+            pointer.copy_from_slice(chunk);
+            match usize::from_ne_bytes(pointer) {
+                0 => return Ok(pointers),
+                p => pointers.push(p as AddressType),
+            }
+        }
+        addr += len;
+    }
+}
+
+// Page sizes are a multiple of this on every architecture
+const MIN_PAGE_SIZE: usize = 4096;
+
+// Returns the number of bytes read
+#[cfg(target_os = "linux")]
+fn read_vm(pid: Pid, addr: usize, buf: &mut [u8]) -> usize {
+    let mut done = 0;
+    while done < buf.len() {
+        let remote = [RemoteIoVec { base: addr + done, len: buf.len() - done }];
+        let local = [IoVec::from_mut_slice(&mut buf[done..])];
+        match process_vm_readv(pid, &local, &remote) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => done += n,
+        }
+    }
+    done
+}
+
+// Returns the number of bytes written
+#[cfg(target_os = "linux")]
+fn write_vm(pid: Pid, addr: usize, buf: &[u8]) -> usize {
+    let mut done = 0;
+    while done < buf.len() {
+        let remote = [RemoteIoVec { base: addr + done, len: buf.len() - done }];
+        let local = [IoVec::from_slice(&buf[done..])];
+        match process_vm_writev(pid, &local, &remote) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => done += n,
+        }
+    }
+    done
+}
+
+const WORD_SIZE: usize = mem::size_of::<c_long>();
+
+fn read_words(pid: Pid, addr: usize, buf: &mut [u8]) -> Result<()> {
+    let mut done = 0;
+    while done < buf.len() {
+        let offset = (addr + done) % WORD_SIZE;
+        let len = cmp::min(WORD_SIZE - offset, buf.len() - done);
+        let word = read(pid, (addr + done - offset) as AddressType)?;
+        buf[done..done + len].copy_from_slice(&word.to_ne_bytes()[offset..offset + len]);
+        done += len;
+    }
+    Ok(())
+}
+
+fn write_words(pid: Pid, addr: usize, buf: &[u8]) -> Result<()> {
+    let mut done = 0;
+    while done < buf.len() {
+        let offset = (addr + done) % WORD_SIZE;
+        let len = cmp::min(WORD_SIZE - offset, buf.len() - done);
+        let word_addr = (addr + done - offset) as AddressType;
+        // Keep the bytes of a partial word that are not overwritten
+        let mut bytes = if len == WORD_SIZE {
+            [0; WORD_SIZE]
+        } else {
+            read(pid, word_addr)?.to_ne_bytes()
+        };
+        bytes[offset..offset + len].copy_from_slice(&buf[done..done + len]);
+        let word = c_long::from_ne_bytes(bytes);
+        unsafe { write(pid, word_addr, word as *mut c_void)? };
+        done += len;
+    }
+    Ok(())
+}
+
+// libc does not export these yet
+const PTRACE_GET_SYSCALL_INFO: RequestType = 0x420e;
+const PTRACE_SYSCALL_INFO_ENTRY: u8 = 1;
+const PTRACE_SYSCALL_INFO_EXIT: u8 = 2;
+const PTRACE_SYSCALL_INFO_SECCOMP: u8 = 3;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct syscall_info_entry {
+    nr: u64,
+    args: [u64; 6],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct syscall_info_exit {
+    rval: i64,
+    is_error: u8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct syscall_info_seccomp {
+    nr: u64,
+    args: [u64; 6],
+    ret_data: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+union syscall_info_data {
+    entry: syscall_info_entry,
+    exit: syscall_info_exit,
+    seccomp: syscall_info_seccomp,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct ptrace_syscall_info {
+    op: u8,
+    pad: [u8; 3],
+    arch: u32,
+    instruction_pointer: u64,
+    stack_pointer: u64,
+    data: syscall_info_data,
+}
+
+/// The system call a tracee is stopped at, as reported by
+/// [`get_syscall_info`](fn.get_syscall_info.html).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SyscallOp {
+    /// The tracee is not stopped at a system call.
+    None,
+    /// Entry to the system call `nr`.
+    Entry { nr: u64, args: [u64; 6] },
+    /// Exit from a system call.  If `is_error` is set, `rval` is a negated
+    /// `errno` value.
+    Exit { rval: i64, is_error: bool },
+    /// A `SECCOMP_RET_TRACE` rule matched the system call `nr`.  `ret_data`
+    /// holds the data of the rule.
+    Seccomp { nr: u64, args: [u64; 6], ret_data: u32 },
+}
+
+/// Information about the system call a tracee is stopped at.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SyscallInfo {
+    /// The `AUDIT_ARCH_*` value of the system call, which differs from the
+    /// tracer's for a 32-bit tracee on a 64-bit kernel.
+    pub arch: u32,
+    pub instruction_pointer: u64,
+    pub stack_pointer: u64,
+    pub op: SyscallOp,
+}
+
+/// Get information about the system call the tracee is stopped at, as with
+/// `ptrace(PTRACE_GET_SYSCALL_INFO, ...)`.
+///
+/// Unlike the registers, this tells a system call entry from an exit
+/// without keeping track of the stops.  It requires Linux 5.3.  The stop must
+/// be a syscall stop reported with `PTRACE_O_TRACESYSGOOD`, or a
+/// `PTRACE_EVENT_SECCOMP` stop; otherwise the result is `SyscallOp::None`.
+pub fn get_syscall_info(pid: Pid) -> Result<SyscallInfo> {
+    let mut info = mem::MaybeUninit::<ptrace_syscall_info>::zeroed();
+    let res = unsafe {
+        libc::ptrace(PTRACE_GET_SYSCALL_INFO,
+                     libc::pid_t::from(pid),
+                     mem::size_of::<ptrace_syscall_info>() as *mut c_void,
+                     info.as_mut_ptr() as *mut c_void)
+    };
+    Errno::result(res)?;
+    let info = unsafe { info.assume_init() };
+
+    let op = unsafe {
+        match info.op {
+            PTRACE_SYSCALL_INFO_ENTRY => SyscallOp::Entry {
+                nr: info.data.entry.nr,
+                args: info.data.entry.args,
+            },
+            PTRACE_SYSCALL_INFO_EXIT => SyscallOp::Exit {
+                rval: info.data.exit.rval,
+                is_error: info.data.exit.is_error != 0,
+            },
+            PTRACE_SYSCALL_INFO_SECCOMP => SyscallOp::Seccomp {
+                nr: info.data.seccomp.nr,
+                args: info.data.seccomp.args,
+                ret_data: info.data.seccomp.ret_data,
+            },
+            _ => SyscallOp::None,
+        }
+    };
+    Ok(SyscallInfo {
+        arch: info.arch,
+        instruction_pointer: info.instruction_pointer,
+        stack_pointer: info.stack_pointer,
+        op,
+    })
+}
+
+/// An event reported by a [`Tracer`](struct.Tracer.html).
+///
+/// The tracee `pid` stays stopped until the next event is requested, so its
+/// registers and memory can be inspected and modified in the meantime.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TraceEvent {
+    /// Entry to the system call `nr`.
+    SyscallEntry { pid: Pid, nr: u64, args: [u64; 6] },
+    /// Exit from a system call.  If `is_error` is set, `rval` is a negated
+    /// `errno` value.
+    SyscallExit { pid: Pid, rval: i64, is_error: bool },
+    /// A `SECCOMP_RET_TRACE` rule matched the system call `nr`.
+    Seccomp { pid: Pid, nr: u64, args: [u64; 6], ret_data: u32 },
+    /// `pid` created the process `child` with `fork`, which is now traced.
+    Fork { pid: Pid, child: Pid },
+    /// `pid` created the process `child` with `vfork`, which is now traced.
+    VFork { pid: Pid, child: Pid },
+    /// `pid` created the thread or process `child` with `clone`, which is
+    /// now traced.
+    Clone { pid: Pid, child: Pid },
+    /// `pid` successfully called `execve`.  If another thread than the thread
+    /// group leader called it, its former thread ID is `former_pid`.
+    Exec { pid: Pid, former_pid: Pid },
+    /// `pid` is about to exit with the wait status `status`.
+    Exiting { pid: Pid, status: libc::c_int },
+    /// `pid` received `signal`.  It is delivered when the tracee is resumed,
+    /// unless [`Tracer::suppress_signal`](struct.Tracer.html#method.suppress_signal)
+    /// is called.
+    Signal { pid: Pid, signal: Signal },
+    /// `pid` entered a group stop because of the stop signal `signal`.
+    GroupStop { pid: Pid, signal: Signal },
+    /// `pid` exited with the exit status `status`, and is no longer traced.
+    Exited { pid: Pid, status: i32 },
+    /// `pid` was killed by `signal`, and is no longer traced.
+    Signaled { pid: Pid, signal: Signal, core_dumped: bool },
+}
+
+/// Traces the system calls of a tracee and of all the threads and processes
+/// it creates, with `PTRACE_SYSCALL`.
+///
+/// Events are read with [`next_event`](#method.next_event), or by iterating
+/// over the `Tracer`.  The iteration ends when no tracee is left.
+///
+/// The `Tracer` relies on [`get_syscall_info`](fn.get_syscall_info.html),
+/// so it requires Linux 5.3.  It reaps the tracees with `waitpid`, so no
+/// other code should wait for any child while it runs.  Tracees created before the `Tracer`,
+/// other than the first one, are not known to it.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use nix::sys::ptrace::{self, Options, TraceEvent, Tracer};
+/// # use nix::sys::signal::{raise, Signal};
+/// # use nix::sys::wait::waitpid;
+/// # use nix::unistd::{execv, fork, ForkResult};
+/// # use std::ffi::CString;
+/// match unsafe { fork() }.unwrap() {
+///     ForkResult::Child => {
+///         ptrace::traceme().unwrap();
+///         raise(Signal::SIGSTOP).unwrap();
+///         let path = CString::new("/bin/true").unwrap();
+///         execv(&path, &[&path]).unwrap();
+///     }
+///     ForkResult::Parent { child } => {
+///         waitpid(child, None).unwrap();
+///         let tracer = Tracer::new(child, Options::PTRACE_O_EXITKILL).unwrap();
+///         for event in tracer {
+///             if let TraceEvent::SyscallEntry { pid, nr, .. } = event.unwrap() {
+///                 println!("{}: syscall {}", pid, nr);
+///             }
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Tracer {
+    tracees: HashSet<Pid>,
+    // Children reported by a fork event, whose initial stop is still to come
+    starting: HashSet<Pid>,
+    // The tracee of the last event, and the signal to deliver to it
+    resume: Option<(Pid, Option<Signal>)>,
+}
+
+impl Tracer {
+    /// Starts tracing `pid`, which must be a tracee in a ptrace stop.
+    ///
+    /// `options` are added to the ones the `Tracer` needs, which are
+    /// `PTRACE_O_TRACESYSGOOD`, `PTRACE_O_TRACEFORK`, `PTRACE_O_TRACEVFORK`,
+    /// `PTRACE_O_TRACECLONE`, `PTRACE_O_TRACEEXEC` and `PTRACE_O_TRACEEXIT`.
+    ///
+    /// Fails with `EIO` before Linux 5.3, which lacks
+    /// `PTRACE_GET_SYSCALL_INFO`.
+    pub fn new(pid: Pid, options: Options) -> Result<Tracer> {
+        // Fail now rather than at every system call
+        get_syscall_info(pid)?;
+        setoptions(pid, options
+                   | Options::PTRACE_O_TRACESYSGOOD
+                   | Options::PTRACE_O_TRACEFORK
+                   | Options::PTRACE_O_TRACEVFORK
+                   | Options::PTRACE_O_TRACECLONE
+                   | Options::PTRACE_O_TRACEEXEC
+                   | Options::PTRACE_O_TRACEEXIT)?;
+
+        let mut tracees = HashSet::new();
+        tracees.insert(pid);
+        Ok(Tracer {
+            tracees,
+            starting: HashSet::new(),
+            resume: Some((pid, None)),
+        })
+    }
+
+    /// Keeps the signal of the last `TraceEvent::Signal` from being
+    /// delivered.
+    pub fn suppress_signal(&mut self) {
+        if let Some((_, ref mut signal)) = self.resume {
+            *signal = None;
+        }
+    }
+
+    /// Resumes the tracee of the last event, and waits for the next event of
+    /// any tracee.  Returns `None` once no tracee is left.
+    pub fn next_event(&mut self) -> Result<Option<TraceEvent>> {
+        loop {
+            if let Some((pid, signal)) = self.resume.take() {
+                match syscall(pid, signal) {
+                    // The tracee was killed in the meantime
+                    Ok(()) | Err(Errno::ESRCH) => (),
+                    Err(e) => return Err(e),
+                }
+            }
+            if self.tracees.is_empty() {
+                return Ok(None);
+            }
+            let status = match waitpid(None, Some(WaitPidFlag::__WALL)) {
+                Ok(status) => status,
+                Err(Errno::EINTR) => continue,
+                Err(Errno::ECHILD) => return Ok(None),
+                Err(e) => return Err(e),
+            };
+            match self.handle(status) {
+                Ok(Some(event)) => return Ok(Some(event)),
+                // The tracee was killed while we were looking at it
+                Ok(None) | Err(Errno::ESRCH) => (),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn handle(&mut self, status: WaitStatus) -> Result<Option<TraceEvent>> {
+        match status {
+            WaitStatus::PtraceSyscall(pid) => {
+                self.resume = Some((pid, None));
+                match get_syscall_info(pid)?.op {
+                    SyscallOp::Entry { nr, args } =>
+                        Ok(Some(TraceEvent::SyscallEntry { pid, nr, args })),
+                    SyscallOp::Exit { rval, is_error } =>
+                        Ok(Some(TraceEvent::SyscallExit { pid, rval, is_error })),
+                    _ => Ok(None),
+                }
+            }
+            WaitStatus::PtraceEvent(pid, _, event) => {
+                self.resume = Some((pid, None));
+                self.handle_event(pid, event)
+            }
+            WaitStatus::PtraceGroupStop(pid, signal) => {
+                self.resume = Some((pid, None));
+                Ok(Some(TraceEvent::GroupStop { pid, signal }))
+            }
+            WaitStatus::Stopped(pid, signal) => {
+                if signal == Signal::SIGSTOP &&
+                    (self.starting.remove(&pid) || self.tracees.insert(pid))
+                {
+                    // The initial stop of a new tracee
+                    self.resume = Some((pid, None));
+                    return Ok(None);
+                }
+                let group_stop = match signal {
+                    signal::SIGSTOP | signal::SIGTSTP | signal::SIGTTIN |
+                        signal::SIGTTOU => getsiginfo(pid) == Err(Errno::EINVAL),
+                    _ => false,
+                };
+                if group_stop {
+                    self.resume = Some((pid, None));
+                    Ok(Some(TraceEvent::GroupStop { pid, signal }))
+                } else {
+                    self.resume = Some((pid, Some(signal)));
+                    Ok(Some(TraceEvent::Signal { pid, signal }))
+                }
+            }
+            WaitStatus::Exited(pid, status) => {
+                self.tracees.remove(&pid);
+                self.starting.remove(&pid);
+                Ok(Some(TraceEvent::Exited { pid, status }))
+            }
+            WaitStatus::Signaled(pid, signal, core_dumped) => {
+                self.tracees.remove(&pid);
+                self.starting.remove(&pid);
+                Ok(Some(TraceEvent::Signaled { pid, signal, core_dumped }))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn handle_event(&mut self, pid: Pid, event: libc::c_int) -> Result<Option<TraceEvent>> {
+        match event {
+            libc::PTRACE_EVENT_FORK | libc::PTRACE_EVENT_VFORK |
+                libc::PTRACE_EVENT_CLONE =>
+            {
+                let child = Pid::from_raw(getevent(pid)? as libc::pid_t);
+                // The initial stop of the child may have come first
+                if self.tracees.insert(child) {
+                    self.starting.insert(child);
+                }
+                Ok(Some(match event {
+                    libc::PTRACE_EVENT_FORK => TraceEvent::Fork { pid, child },
+                    libc::PTRACE_EVENT_VFORK => TraceEvent::VFork { pid, child },
+                    _ => TraceEvent::Clone { pid, child },
+                }))
+            }
+            libc::PTRACE_EVENT_EXEC => {
+                let former_pid = Pid::from_raw(getevent(pid)? as libc::pid_t);
+                if former_pid != pid {
+                    self.tracees.remove(&former_pid);
+                }
+                Ok(Some(TraceEvent::Exec { pid, former_pid }))
+            }
+            libc::PTRACE_EVENT_EXIT => {
+                let status = getevent(pid)? as libc::c_int;
+                Ok(Some(TraceEvent::Exiting { pid, status }))
+            }
+            libc::PTRACE_EVENT_SECCOMP => match get_syscall_info(pid)?.op {
+                SyscallOp::Seccomp { nr, args, ret_data } =>
+                    Ok(Some(TraceEvent::Seccomp { pid, nr, args, ret_data })),
+                _ => Ok(None),
+            },
+            _ => Ok(None),
+        }
+    }
+}
+
+impl Iterator for Tracer {
+    type Item = Result<TraceEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_event().transpose()
+    }
+}
@@ -0,0 +1,640 @@
+//! Restrict the system calls a thread can make
+//!
+//! A [`Filter`](struct.Filter.html) is a list of rules that match system
+//! calls by number and arguments, and decide what happens to them.  It is
+//! compiled to a classic BPF program and installed with
+//! [`Filter::install`](struct.Filter.html#method.install).
+//!
+//! Matched system calls may also be forwarded to a supervisor, which reads
+//! them from a [`Listener`](struct.Listener.html) and answers on behalf of
+//! the kernel.
+//!
+//! See [`seccomp(2)`](https://man7.org/linux/man-pages/man2/seccomp.2.html)
+//! and [`seccomp_unotify(2)`](https://man7.org/linux/man-pages/man2/seccomp_unotify.2.html).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use nix::errno::Errno;
+//! use nix::sys::seccomp::*;
+//!
+//! set_no_new_privs().unwrap();
+//! Filter::new(Action::Allow)
+//!     .rule(libc::SYS_ptrace, Action::Errno(Errno::EPERM))
+//!     .rule_if(libc::SYS_socket,
+//!              &[ArgCmp::new(0, CmpOp::Ne, libc::AF_UNIX as u64)],
+//!              Action::Errno(Errno::EACCES))
+//!     .install(FilterFlags::empty())
+//!     .unwrap();
+//! ```
+use std::convert::TryFrom;
+use std::mem;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::ptr;
+use crate::Result;
+use crate::errno::Errno;
+use crate::fcntl::OFlag;
+use crate::unistd::{self, Pid};
+
+use libc::{self, c_int, c_long, c_uint, c_ulong, c_void, sock_filter, sock_fprog};
+
+bitflags::bitflags! {
+    /// Flags for [`Filter::install`](struct.Filter.html#method.install).
+    pub struct FilterFlags: c_ulong {
+        /// Install the filter on all threads of the process.  If a thread
+        /// can't be synchronized, `install` fails with `ESRCH` and
+        /// [`set_mode_filter`](fn.set_mode_filter.html) returns its thread
+        /// ID.
+        const SECCOMP_FILTER_FLAG_TSYNC = libc::SECCOMP_FILTER_FLAG_TSYNC;
+        /// Log all the actions taken by the filter, except `Allow`.
+        const SECCOMP_FILTER_FLAG_LOG = libc::SECCOMP_FILTER_FLAG_LOG;
+        /// Don't enable the speculative store bypass mitigation.
+        const SECCOMP_FILTER_FLAG_SPEC_ALLOW = libc::SECCOMP_FILTER_FLAG_SPEC_ALLOW;
+        /// Return a [`Listener`](struct.Listener.html) for the
+        /// `Action::UserNotif` rules of the filter.  Since Linux 5.0.
+        // libc does not export this and SECCOMP_FILTER_FLAG_TSYNC_ESRCH yet
+        const SECCOMP_FILTER_FLAG_NEW_LISTENER = 1 << 3;
+        /// With `SECCOMP_FILTER_FLAG_TSYNC`, fail with `ESRCH` instead of
+        /// returning a thread ID, which makes it usable with
+        /// `SECCOMP_FILTER_FLAG_NEW_LISTENER`.  Since Linux 5.7.
+        const SECCOMP_FILTER_FLAG_TSYNC_ESRCH = 1 << 4;
+    }
+}
+
+// libc does not export these yet
+const SECCOMP_SET_MODE_STRICT: c_uint = 0;
+const SECCOMP_SET_MODE_FILTER: c_uint = 1;
+const SECCOMP_RET_USER_NOTIF: u32 = 0x7fc0_0000;
+const SECCOMP_USER_NOTIF_FLAG_CONTINUE: u32 = 1;
+const SECCOMP_ADDFD_FLAG_SETFD: u32 = 1;
+
+// Classic BPF opcodes from linux/filter.h
+const BPF_LD_W_ABS: u16 = 0x20;  // BPF_LD | BPF_W | BPF_ABS
+const BPF_ALU_AND_K: u16 = 0x54; // BPF_ALU | BPF_AND | BPF_K
+const BPF_JMP_JEQ_K: u16 = 0x15; // BPF_JMP | BPF_JEQ | BPF_K
+const BPF_JMP_JGT_K: u16 = 0x25; // BPF_JMP | BPF_JGT | BPF_K
+const BPF_JMP_JGE_K: u16 = 0x35; // BPF_JMP | BPF_JGE | BPF_K
+const BPF_RET_K: u16 = 0x06;     // BPF_RET | BPF_K
+const BPF_MAXINSNS: usize = 4096;
+
+// The AUDIT_ARCH_* value of the target, from linux/audit.h
+#[cfg(target_arch = "x86")]
+const AUDIT_ARCH: u32 = 0x4000_0003;
+#[cfg(target_arch = "x86_64")]
+const AUDIT_ARCH: u32 = 0xc000_003e;
+#[cfg(target_arch = "arm")]
+const AUDIT_ARCH: u32 = 0x4000_0028;
+#[cfg(target_arch = "aarch64")]
+const AUDIT_ARCH: u32 = 0xc000_00b7;
+#[cfg(all(target_arch = "mips", target_endian = "big"))]
+const AUDIT_ARCH: u32 = 0x0000_0008;
+#[cfg(all(target_arch = "mips", target_endian = "little"))]
+const AUDIT_ARCH: u32 = 0x4000_0008;
+#[cfg(all(target_arch = "mips64", target_endian = "big"))]
+const AUDIT_ARCH: u32 = 0x8000_0008;
+#[cfg(all(target_arch = "mips64", target_endian = "little"))]
+const AUDIT_ARCH: u32 = 0xc000_0008;
+#[cfg(target_arch = "powerpc")]
+const AUDIT_ARCH: u32 = 0x0000_0014;
+#[cfg(all(target_arch = "powerpc64", target_endian = "big"))]
+const AUDIT_ARCH: u32 = 0x8000_0015;
+#[cfg(all(target_arch = "powerpc64", target_endian = "little"))]
+const AUDIT_ARCH: u32 = 0xc000_0015;
+#[cfg(target_arch = "riscv64")]
+const AUDIT_ARCH: u32 = 0xc000_00f3;
+#[cfg(target_arch = "s390x")]
+const AUDIT_ARCH: u32 = 0x8000_0016;
+#[cfg(target_arch = "sparc64")]
+const AUDIT_ARCH: u32 = 0x8000_002b;
+
+// x32 system calls use the x86_64 AUDIT_ARCH, with this bit set in their
+// number, from asm/unistd.h
+#[cfg(target_arch = "x86_64")]
+const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+
+// Offsets of the fields of libc::seccomp_data
+const DATA_NR: u32 = 0;
+const DATA_ARCH: u32 = 4;
+const DATA_ARGS: u32 = 16;
+
+/// What happens to a system call matched by a rule, in decreasing order of
+/// precedence when several filters match.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Action {
+    /// Kill the whole process with `SIGSYS`.  Since Linux 4.14.
+    KillProcess,
+    /// Kill the calling thread with `SIGSYS`.
+    KillThread,
+    /// Send `SIGSYS` to the calling thread, with the given value in
+    /// `si_errno`.
+    Trap(u16),
+    /// Fail the system call with the given error.
+    Errno(Errno),
+    /// Forward the system call to the supervisor holding the
+    /// [`Listener`](struct.Listener.html) of the filter.  Since Linux 5.0.
+    UserNotif,
+    /// Notify the `ptrace(2)` tracer with `PTRACE_EVENT_SECCOMP`, and the
+    /// given value as event message.  The system call fails with `ENOSYS`
+    /// if there is no tracer.
+    Trace(u16),
+    /// Log and allow the system call.  Since Linux 4.14.
+    Log,
+    /// Allow the system call.
+    Allow,
+}
+
+impl Action {
+    fn ret(self) -> u32 {
+        match self {
+            Action::KillProcess => libc::SECCOMP_RET_KILL_PROCESS,
+            Action::KillThread => libc::SECCOMP_RET_KILL_THREAD,
+            Action::Trap(data) => libc::SECCOMP_RET_TRAP | u32::from(data),
+            Action::Errno(errno) => {
+                libc::SECCOMP_RET_ERRNO | (errno as u32 & libc::SECCOMP_RET_DATA)
+            },
+            Action::UserNotif => SECCOMP_RET_USER_NOTIF,
+            Action::Trace(data) => libc::SECCOMP_RET_TRACE | u32::from(data),
+            Action::Log => libc::SECCOMP_RET_LOG,
+            Action::Allow => libc::SECCOMP_RET_ALLOW,
+        }
+    }
+}
+
+/// How an argument of a system call is compared by an
+/// [`ArgCmp`](struct.ArgCmp.html).
+///
+/// Arguments are compared as unsigned 64-bit integers.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CmpOp {
+    /// The argument equals the value.
+    Eq,
+    /// The argument differs from the value.
+    Ne,
+    /// The argument is less than the value.
+    Lt,
+    /// The argument is less than or equal to the value.
+    Le,
+    /// The argument is greater than the value.
+    Gt,
+    /// The argument is greater than or equal to the value.
+    Ge,
+    /// The argument, masked with the given mask, equals the value.
+    MaskedEq(u64),
+}
+
+/// A condition on an argument of a system call.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ArgCmp {
+    arg: u8,
+    op: CmpOp,
+    value: u64,
+}
+
+impl ArgCmp {
+    /// Compare the argument number `arg`, starting from 0, with `value`.
+    ///
+    /// There are at most 6 arguments; rules with conditions on other
+    /// arguments fail to compile with `EINVAL`.
+    pub fn new(arg: u8, op: CmpOp, value: u64) -> ArgCmp {
+        ArgCmp { arg, op, value }
+    }
+}
+
+// Where a jump of a comparison goes: to the next instruction, to the next
+// comparison of the rule, or to the next rule.
+#[derive(Clone, Copy)]
+enum Jump {
+    Next,
+    Pass,
+    Fail,
+}
+
+struct Insn {
+    code: u16,
+    jt: Jump,
+    jf: Jump,
+    k: u32,
+}
+
+fn stmt(code: u16, k: u32) -> Insn {
+    Insn { code, jt: Jump::Next, jf: Jump::Next, k }
+}
+
+fn jump(code: u16, k: u32, jt: Jump, jf: Jump) -> Insn {
+    Insn { code, jt, jf, k }
+}
+
+impl ArgCmp {
+    // Compile the comparison as two 32-bit ones, high word first
+    fn compile(&self) -> Result<Vec<Insn>> {
+        if self.arg >= 6 {
+            return Err(Errno::EINVAL);
+        }
+        let offset = DATA_ARGS + 8 * u32::from(self.arg);
+        let (lo, hi) = if cfg!(target_endian = "little") {
+            (offset, offset + 4)
+        } else {
+            (offset + 4, offset)
+        };
+        let (value_lo, value_hi) = (self.value as u32, (self.value >> 32) as u32);
+        let (insns, negate) = match self.op {
+            CmpOp::Eq | CmpOp::Ne => (vec![
+                stmt(BPF_LD_W_ABS, hi),
+                jump(BPF_JMP_JEQ_K, value_hi, Jump::Next, Jump::Fail),
+                stmt(BPF_LD_W_ABS, lo),
+                jump(BPF_JMP_JEQ_K, value_lo, Jump::Pass, Jump::Fail),
+            ], self.op == CmpOp::Ne),
+            CmpOp::Gt | CmpOp::Le => (vec![
+                stmt(BPF_LD_W_ABS, hi),
+                jump(BPF_JMP_JGT_K, value_hi, Jump::Pass, Jump::Next),
+                jump(BPF_JMP_JEQ_K, value_hi, Jump::Next, Jump::Fail),
+                stmt(BPF_LD_W_ABS, lo),
+                jump(BPF_JMP_JGT_K, value_lo, Jump::Pass, Jump::Fail),
+            ], self.op == CmpOp::Le),
+            CmpOp::Ge | CmpOp::Lt => (vec![
+                stmt(BPF_LD_W_ABS, hi),
+                jump(BPF_JMP_JGT_K, value_hi, Jump::Pass, Jump::Next),
+                jump(BPF_JMP_JEQ_K, value_hi, Jump::Next, Jump::Fail),
+                stmt(BPF_LD_W_ABS, lo),
+                jump(BPF_JMP_JGE_K, value_lo, Jump::Pass, Jump::Fail),
+            ], self.op == CmpOp::Lt),
+            CmpOp::MaskedEq(mask) => (vec![
+                stmt(BPF_LD_W_ABS, hi),
+                stmt(BPF_ALU_AND_K, (mask >> 32) as u32),
+                jump(BPF_JMP_JEQ_K, value_hi, Jump::Next, Jump::Fail),
+                stmt(BPF_LD_W_ABS, lo),
+                stmt(BPF_ALU_AND_K, mask as u32),
+                jump(BPF_JMP_JEQ_K, value_lo, Jump::Pass, Jump::Fail),
+            ], false),
+        };
+        let swap = |target| match target {
+            Jump::Pass => Jump::Fail,
+            Jump::Fail => Jump::Pass,
+            Jump::Next => Jump::Next,
+        };
+        Ok(insns.into_iter().map(|insn| {
+            if negate {
+                Insn { jt: swap(insn.jt), jf: swap(insn.jf), ..insn }
+            } else {
+                insn
+            }
+        }).collect())
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Rule {
+    syscall: c_long,
+    args: Vec<ArgCmp>,
+    action: Action,
+}
+
+impl Rule {
+    fn compile(&self) -> Result<Vec<sock_filter>> {
+        let mut insns = vec![
+            stmt(BPF_LD_W_ABS, DATA_NR),
+            jump(BPF_JMP_JEQ_K, self.syscall as u32, Jump::Next, Jump::Fail),
+        ];
+        // Indices of the first instruction after each comparison
+        let mut ends = Vec::with_capacity(self.args.len());
+        for arg in &self.args {
+            insns.extend(arg.compile()?);
+            ends.push(insns.len());
+        }
+        insns.push(stmt(BPF_RET_K, self.action.ret()));
+
+        let len = insns.len();
+        let mut end = ends.iter().peekable();
+        insns.iter().enumerate().map(|(i, insn)| {
+            while end.peek().filter(|&&&end| end <= i).is_some() {
+                end.next();
+            }
+            let pass = end.peek().map_or(len, |&&end| end);
+            let offset = |target| {
+                let to = match target {
+                    Jump::Next => i + 1,
+                    Jump::Pass => pass,
+                    Jump::Fail => len,
+                };
+                u8::try_from(to - i - 1).map_err(|_| Errno::E2BIG)
+            };
+            Ok(sock_filter { code: insn.code, jt: offset(insn.jt)?, jf: offset(insn.jf)?, k: insn.k })
+        }).collect()
+    }
+}
+
+/// A seccomp filter, built from a list of rules.
+///
+/// The first rule that matches a system call decides what happens to it;
+/// the default action applies to system calls that match no rule.  System
+/// calls made with the ABI of another architecture, like 32-bit or x32
+/// system calls on x86_64, kill the process.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Filter {
+    default: Action,
+    rules: Vec<Rule>,
+}
+
+impl Filter {
+    /// Create a filter that applies `default` to all system calls.
+    pub fn new(default: Action) -> Filter {
+        Filter { default, rules: Vec::new() }
+    }
+
+    /// Apply `action` to the system call `syscall`, like `libc::SYS_open`.
+    pub fn rule(self, syscall: c_long, action: Action) -> Self {
+        self.rule_if(syscall, &[], action)
+    }
+
+    /// Apply `action` to the system call `syscall` when all the conditions
+    /// on its arguments are true.
+    pub fn rule_if(mut self, syscall: c_long, args: &[ArgCmp], action: Action) -> Self {
+        self.rules.push(Rule { syscall, args: args.to_vec(), action });
+        self
+    }
+
+    /// Compile the filter to a classic BPF program.
+    ///
+    /// This fails with `EINVAL` if a condition is on a nonexistent argument,
+    /// and with `E2BIG` if the program is too long.
+    pub fn compile(&self) -> Result<Vec<sock_filter>> {
+        let mut program = vec![
+            sock_filter { code: BPF_LD_W_ABS, jt: 0, jf: 0, k: DATA_ARCH },
+            sock_filter { code: BPF_JMP_JEQ_K, jt: 1, jf: 0, k: AUDIT_ARCH },
+            sock_filter { code: BPF_RET_K, jt: 0, jf: 0, k: libc::SECCOMP_RET_KILL_PROCESS },
+        ];
+        // Otherwise x32 system calls would get past the rules, which only
+        // compare the number without that bit
+        #[cfg(target_arch = "x86_64")]
+        program.extend_from_slice(&[
+            sock_filter { code: BPF_LD_W_ABS, jt: 0, jf: 0, k: DATA_NR },
+            sock_filter { code: BPF_JMP_JGE_K, jt: 0, jf: 1, k: X32_SYSCALL_BIT },
+            sock_filter { code: BPF_RET_K, jt: 0, jf: 0, k: libc::SECCOMP_RET_KILL_PROCESS },
+        ]);
+        for rule in &self.rules {
+            program.extend(rule.compile()?);
+        }
+        program.push(sock_filter { code: BPF_RET_K, jt: 0, jf: 0, k: self.default.ret() });
+        if program.len() > BPF_MAXINSNS {
+            return Err(Errno::E2BIG);
+        }
+        Ok(program)
+    }
+
+    /// Compile the filter and install it on the calling thread, or on all
+    /// threads with `SECCOMP_FILTER_FLAG_TSYNC`.
+    ///
+    /// If another thread can't be synchronized with
+    /// `SECCOMP_FILTER_FLAG_TSYNC`, the filter isn't installed and `ESRCH`
+    /// is returned.
+    ///
+    /// With `SECCOMP_FILTER_FLAG_NEW_LISTENER`, a
+    /// [`Listener`](struct.Listener.html) is returned as well.
+    ///
+    /// Unless the thread has `CAP_SYS_ADMIN`,
+    /// [`set_no_new_privs`](fn.set_no_new_privs.html) must be called first.
+    pub fn install(&self, flags: FilterFlags) -> Result<Option<Listener>> {
+        let program = self.compile()?;
+        let res = set_mode_filter(&program, flags)?;
+        if flags.contains(FilterFlags::SECCOMP_FILTER_FLAG_NEW_LISTENER) {
+            Ok(Some(Listener(res)))
+        } else if res > 0 {
+            // The ID of a thread that kept TSYNC from installing the filter
+            Err(Errno::ESRCH)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Forbid the calling thread and its future children to gain privileges,
+/// like by executing set-user-ID programs
+/// ([`prctl(2)`](https://man7.org/linux/man-pages/man2/prctl.2.html)).
+///
+/// This can't be undone.
+pub fn set_no_new_privs() -> Result<()> {
+    let res = unsafe {
+        libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1 as c_ulong, 0 as c_ulong,
+                    0 as c_ulong, 0 as c_ulong)
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Only allow the calling thread to use `read(2)`, `write(2)`, `_exit(2)`
+/// and `sigreturn(2)`; other system calls kill it with `SIGKILL`.
+///
+/// Note that `libc::_exit` calls `exit_group(2)`, so the thread can only
+/// terminate by calling `libc::SYS_exit` directly.
+pub fn set_mode_strict() -> Result<()> {
+    let res = unsafe {
+        libc::syscall(libc::SYS_seccomp, SECCOMP_SET_MODE_STRICT, 0 as c_uint,
+                      ptr::null::<c_void>())
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Install a classic BPF program as seccomp filter, which is what
+/// [`Filter::install`](struct.Filter.html#method.install) does with its
+/// compiled rules.
+///
+/// The result depends on `flags`: a new listener file descriptor with
+/// `SECCOMP_FILTER_FLAG_NEW_LISTENER`, and zero otherwise.  With
+/// `SECCOMP_FILTER_FLAG_TSYNC` but not `SECCOMP_FILTER_FLAG_TSYNC_ESRCH`, it
+/// is instead the ID of a thread that can't be synchronized, in which case
+/// the filter was not installed.
+pub fn set_mode_filter(program: &[sock_filter], flags: FilterFlags) -> Result<c_int> {
+    if program.len() > BPF_MAXINSNS {
+        return Err(Errno::EINVAL);
+    }
+    let prog = sock_fprog {
+        len: program.len() as libc::c_ushort,
+        filter: program.as_ptr() as *mut sock_filter,
+    };
+    let res = unsafe {
+        libc::syscall(libc::SYS_seccomp, SECCOMP_SET_MODE_FILTER, flags.bits(),
+                      &prog as *const sock_fprog)
+    };
+
+    Errno::result(res).map(|res| res as c_int)
+}
+
+// libc does not export the user notification structures yet
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct seccomp_notif {
+    id: u64,
+    pid: u32,
+    flags: u32,
+    data: libc::seccomp_data,
+}
+
+#[repr(C)]
+struct seccomp_notif_resp {
+    id: u64,
+    val: i64,
+    error: i32,
+    flags: u32,
+}
+
+#[repr(C)]
+struct seccomp_notif_addfd {
+    id: u64,
+    flags: u32,
+    srcfd: u32,
+    newfd: u32,
+    newfd_flags: u32,
+}
+
+// User notification ioctls from linux/seccomp.h
+mod notif {
+    use super::{seccomp_notif, seccomp_notif_addfd, seccomp_notif_resp};
+
+    ioctl_readwrite!(notif_recv, b'!', 0, seccomp_notif);
+    ioctl_readwrite!(notif_send, b'!', 1, seccomp_notif_resp);
+    ioctl_write_ptr!(notif_id_valid, b'!', 2, u64);
+    ioctl_write_ptr!(notif_addfd, b'!', 3, seccomp_notif_addfd);
+}
+
+/// A system call forwarded to the supervisor by `Action::UserNotif`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Notification {
+    /// Cookie identifying the notification in the replies to it.
+    pub id: u64,
+    /// Thread that made the system call, as seen from the PID namespace of
+    /// the supervisor; zero if it is not visible there.
+    pub pid: Pid,
+    /// System call number.
+    pub syscall: c_int,
+    /// `AUDIT_ARCH_*` value of the architecture of the system call.
+    pub arch: u32,
+    /// Address of the instruction that made the system call.
+    pub instruction_pointer: u64,
+    /// Arguments of the system call.
+    pub args: [u64; 6],
+}
+
+/// Reply to a [`Notification`](struct.Notification.html).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Response {
+    /// Make the system call return this value.
+    Value(i64),
+    /// Make the system call fail with this error.
+    Error(Errno),
+    /// Let the kernel carry out the system call.  Since Linux 5.5.
+    ///
+    /// The arguments may have been changed by another thread of the target
+    /// since they were notified, so this must not be used to grant
+    /// permissions based on them.
+    Continue,
+}
+
+/// A file descriptor to receive the system calls that filters forward with
+/// `Action::UserNotif`, and reply to them.
+///
+/// It is readable, in the `poll(2)` sense, when a notification is pending.
+/// This type implements `Drop`, so the file descriptor is closed when it
+/// goes out of scope; pending and future notifications then fail with
+/// `ENOSYS`.
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct Listener(RawFd);
+
+impl Listener {
+    /// Wait for the next notification
+    /// (`SECCOMP_IOCTL_NOTIF_RECV`).
+    pub fn recv(&self) -> Result<Notification> {
+        // The kernel requires a zeroed structure
+        let mut notif: seccomp_notif = unsafe { mem::zeroed() };
+        unsafe { notif::notif_recv(self.0, &mut notif) }?;
+        Ok(Notification {
+            id: notif.id,
+            pid: Pid::from_raw(notif.pid as libc::pid_t),
+            syscall: notif.data.nr,
+            arch: notif.data.arch,
+            instruction_pointer: notif.data.instruction_pointer,
+            args: notif.data.args,
+        })
+    }
+
+    /// Reply to the notification `id`, which resumes the thread that made
+    /// the system call (`SECCOMP_IOCTL_NOTIF_SEND`).
+    ///
+    /// This fails with `ENOENT` if the system call was interrupted, or the
+    /// thread killed, in the meantime.
+    pub fn send(&self, id: u64, response: Response) -> Result<()> {
+        let mut resp = seccomp_notif_resp { id, val: 0, error: 0, flags: 0 };
+        match response {
+            Response::Value(val) => resp.val = val,
+            Response::Error(errno) => resp.error = -(errno as i32),
+            Response::Continue => resp.flags = SECCOMP_USER_NOTIF_FLAG_CONTINUE,
+        }
+        unsafe { notif::notif_send(self.0, &mut resp) }.map(drop)
+    }
+
+    /// Whether the notification `id` is still pending
+    /// (`SECCOMP_IOCTL_NOTIF_ID_VALID`).
+    ///
+    /// Supervisors that read the memory of the target, like paths passed as
+    /// arguments, must check this afterwards: the thread may have been
+    /// killed and its PID reused in the meantime.
+    pub fn is_valid(&self, id: u64) -> Result<bool> {
+        match unsafe { notif::notif_id_valid(self.0, &id) } {
+            Ok(_) => Ok(true),
+            Err(Errno::ENOENT) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Duplicate `srcfd` into the target of the notification `id`, and
+    /// return its number there (`SECCOMP_IOCTL_NOTIF_ADDFD`).  Since Linux
+    /// 5.9.
+    ///
+    /// The file descriptor is installed as `newfd` if given, replacing any
+    /// open one, and as the lowest available one otherwise.  The only flag
+    /// allowed in `newfd_flags` is `O_CLOEXEC`.
+    pub fn add_fd(&self, id: u64, srcfd: RawFd, newfd: Option<RawFd>, newfd_flags: OFlag)
+        -> Result<RawFd>
+    {
+        let addfd = seccomp_notif_addfd {
+            id,
+            flags: if newfd.is_some() { SECCOMP_ADDFD_FLAG_SETFD } else { 0 },
+            srcfd: srcfd as u32,
+            newfd: newfd.unwrap_or(0) as u32,
+            newfd_flags: newfd_flags.bits() as u32,
+        };
+        unsafe { notif::notif_addfd(self.0, &addfd) }
+    }
+}
+
+impl AsRawFd for Listener {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+impl FromRawFd for Listener {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Listener(fd)
+    }
+}
+
+impl IntoRawFd for Listener {
+    fn into_raw_fd(self) -> RawFd {
+        let fd = self.0;
+        mem::forget(self);
+        fd
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        // Once the listener is closed, the system calls it was notified of
+        // fail with ENOSYS in the filtered process.  EBADF means a double
+        // close.
+        let e = unistd::close(self.0);
+        if e == Err(Errno::EBADF) {
+            panic!("Closing an invalid file descriptor!");
+        };
+    }
+}
@@ -16,62 +16,86 @@ use std::ptr;
 
 #[cfg(not(any(target_os = "openbsd", target_os = "redox")))]
 pub use self::sigevent::*;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub use self::siginfo::*;
+#[cfg(not(target_os = "redox"))]
+pub use self::signal_pipe::*;
 
-libc_enum!{
-    // Currently there is only one definition of c_int in libc, as well as only one
-    // type for signal constants.
-    // We would prefer to use the libc::c_int alias in the repr attribute. Unfortunately
-    // this is not (yet) possible.
-    #[repr(i32)]
-    pub enum Signal {
-        SIGHUP,
-        SIGINT,
-        SIGQUIT,
-        SIGILL,
-        SIGTRAP,
-        SIGABRT,
-        SIGBUS,
-        SIGFPE,
-        SIGKILL,
-        SIGUSR1,
-        SIGSEGV,
-        SIGUSR2,
-        SIGPIPE,
-        SIGALRM,
-        SIGTERM,
-        #[cfg(all(any(target_os = "android", target_os = "emscripten",
-                      target_os = "fuchsia", target_os = "linux"),
-                  not(any(target_arch = "mips", target_arch = "mips64",
-                          target_arch = "sparc64"))))]
-        SIGSTKFLT,
-        SIGCHLD,
-        SIGCONT,
-        SIGSTOP,
-        SIGTSTP,
-        SIGTTIN,
-        SIGTTOU,
-        SIGURG,
-        SIGXCPU,
-        SIGXFSZ,
-        SIGVTALRM,
-        SIGPROF,
-        SIGWINCH,
-        SIGIO,
-        #[cfg(any(target_os = "android", target_os = "emscripten",
-                  target_os = "fuchsia", target_os = "linux"))]
-        SIGPWR,
-        SIGSYS,
-        #[cfg(not(any(target_os = "android", target_os = "emscripten",
-                      target_os = "fuchsia", target_os = "linux",
-                      target_os = "redox")))]
-        SIGEMT,
-        #[cfg(not(any(target_os = "android", target_os = "emscripten",
-                      target_os = "fuchsia", target_os = "linux",
-                      target_os = "redox")))]
-        SIGINFO,
+/// An operating system signal.
+///
+/// Besides the signals defined as constants, Linux and Android support
+/// real-time signals, from [`Signal::rtmin`](#method.rtmin) to
+/// [`Signal::rtmax`](#method.rtmax).  Their numbers are only known at run
+/// time, because the C library reserves some of them for its own use.
+#[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Signal(libc::c_int);
+
+// Define each signal both as an associated constant, like `Signal::SIGINT`,
+// and as a constant of this module, like `signal::SIGINT`.
+macro_rules! signals {
+    ($($(#[$attr:meta])* $name:ident),* $(,)*) => {
+        impl Signal {
+            $(
+                $(#[$attr])*
+                pub const $name: Signal = Signal(libc::$name);
+            )*
+        }
+
+        $(
+            $(#[$attr])*
+            pub const $name: Signal = Signal::$name;
+        )*
     }
 }
 
+signals! {
+    SIGHUP,
+    SIGINT,
+    SIGQUIT,
+    SIGILL,
+    SIGTRAP,
+    SIGABRT,
+    SIGBUS,
+    SIGFPE,
+    SIGKILL,
+    SIGUSR1,
+    SIGSEGV,
+    SIGUSR2,
+    SIGPIPE,
+    SIGALRM,
+    SIGTERM,
+    #[cfg(all(any(target_os = "android", target_os = "emscripten",
+                  target_os = "fuchsia", target_os = "linux"),
+              not(any(target_arch = "mips", target_arch = "mips64",
+                      target_arch = "sparc64"))))]
+    SIGSTKFLT,
+    SIGCHLD,
+    SIGCONT,
+    SIGSTOP,
+    SIGTSTP,
+    SIGTTIN,
+    SIGTTOU,
+    SIGURG,
+    SIGXCPU,
+    SIGXFSZ,
+    SIGVTALRM,
+    SIGPROF,
+    SIGWINCH,
+    SIGIO,
+    #[cfg(any(target_os = "android", target_os = "emscripten",
+              target_os = "fuchsia", target_os = "linux"))]
+    SIGPWR,
+    SIGSYS,
+    #[cfg(not(any(target_os = "android", target_os = "emscripten",
+                  target_os = "fuchsia", target_os = "linux",
+                  target_os = "redox")))]
+    SIGEMT,
+    #[cfg(not(any(target_os = "android", target_os = "emscripten",
+                  target_os = "fuchsia", target_os = "linux",
+                  target_os = "redox")))]
+    SIGINFO,
+}
+
 impl FromStr for Signal {
     type Err = Error;
     fn from_str(s: &str) -> Result<Signal> {
@@ -121,6 +145,8 @@ impl FromStr for Signal {
                           target_os = "fuchsia", target_os = "linux",
                           target_os = "redox")))]
             "SIGINFO" => Signal::SIGINFO,
+            #[cfg(any(target_os = "android", target_os = "linux"))]
+            s if s.starts_with("SIGRTM") => return Signal::from_rt_name(s),
             _ => return Err(Error::from(Errno::EINVAL)),
         })
     }
@@ -132,6 +158,12 @@ impl Signal {
     /// This function is equivalent to `<Signal as AsRef<str>>::as_ref()`,
     /// with difference that returned string is `'static`
     /// and not bound to `self`'s lifetime.
+    ///
+    /// Real-time signals are named after their offset from `SIGRTMIN`, like
+    /// `SIGRTMIN+3`, up to `SIGRTMIN+32`.  The ones the C library reserves
+    /// below `SIGRTMIN` are named `SIGRTMIN-1` and so on.  Beyond that, which only happens on
+    /// MIPS, they are all named `SIGRT`; their `Display` implementation is
+    /// always accurate.
     pub fn as_str(self) -> &'static str {
         match self {
             Signal::SIGHUP => "SIGHUP",
@@ -178,10 +210,102 @@ impl Signal {
                           target_os = "fuchsia", target_os = "linux",
                           target_os = "redox")))]
             Signal::SIGINFO => "SIGINFO",
+            #[cfg(any(target_os = "android", target_os = "linux"))]
+            signal if signal.is_realtime() && signal.0 < libc::SIGRTMIN() => {
+                RT_RESERVED_NAMES.get((libc::SIGRTMIN() - signal.0 - 1) as usize)
+                    .cloned()
+                    .unwrap_or("SIGRT")
+            },
+            #[cfg(any(target_os = "android", target_os = "linux"))]
+            signal if signal.is_realtime() => {
+                RT_NAMES.get((signal.0 - libc::SIGRTMIN()) as usize)
+                    .cloned()
+                    .unwrap_or("SIGRT")
+            },
+            _ => unreachable!("unknown signal {}", self.0),
+        }
+    }
+
+    /// Returns the number of the signal.
+    pub fn as_raw(self) -> libc::c_int {
+        self.0
+    }
+
+    /// The first real-time signal available to applications.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn rtmin() -> Signal {
+        Signal(libc::SIGRTMIN())
+    }
+
+    /// The last real-time signal.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn rtmax() -> Signal {
+        Signal(libc::SIGRTMAX())
+    }
+
+    /// The real-time signal `SIGRTMIN+n`.
+    ///
+    /// Fails with `EINVAL` if it is beyond `SIGRTMAX`.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn rt(n: libc::c_int) -> Result<Signal> {
+        if n >= 0 && n <= libc::SIGRTMAX() - libc::SIGRTMIN() {
+            Ok(Signal(libc::SIGRTMIN() + n))
+        } else {
+            Err(Error::from(Errno::EINVAL))
+        }
+    }
+
+    /// Whether this is a real-time signal.  This includes the ones below
+    /// `SIGRTMIN` that the C library reserves for its own use, which other
+    /// programs may still send.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn is_realtime(self) -> bool {
+        KERNEL_SIGRTMIN <= self.0 && self.0 <= libc::SIGRTMAX()
+    }
+
+    // Parse SIGRTMIN, SIGRTMIN+n, SIGRTMIN-n, SIGRTMAX or SIGRTMAX-n
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn from_rt_name(s: &str) -> Result<Signal> {
+        let (base, rest) = match (s.get(.."SIGRTMIN".len()), s.get("SIGRTMIN".len()..)) {
+            (Some("SIGRTMIN"), Some(rest)) => (libc::SIGRTMIN(), rest),
+            (Some("SIGRTMAX"), Some(rest)) if !rest.starts_with('+') => {
+                (libc::SIGRTMAX(), rest)
+            },
+            _ => return Err(Error::from(Errno::EINVAL)),
+        };
+        let mut chars = rest.chars();
+        let offset = match (chars.next(), chars.as_str().parse::<libc::c_int>()) {
+            (None, _) => 0,
+            (Some('+'), Ok(n)) if n >= 0 => n,
+            (Some('-'), Ok(n)) if n >= 0 => -n,
+            _ => return Err(Error::from(Errno::EINVAL)),
+        };
+        match base.checked_add(offset).map(Signal) {
+            Some(signal) if signal.is_realtime() => Ok(signal),
+            _ => Err(Error::from(Errno::EINVAL)),
         }
     }
 }
 
+// The first real-time signal of the kernel.  libc only exports the SIGRTMIN
+// of the C library, which keeps the first few for itself.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+const KERNEL_SIGRTMIN: libc::c_int = 32;
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+const RT_RESERVED_NAMES: [&str; 3] = ["SIGRTMIN-1", "SIGRTMIN-2", "SIGRTMIN-3"];
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+const RT_NAMES: [&str; 33] = [
+    "SIGRTMIN", "SIGRTMIN+1", "SIGRTMIN+2", "SIGRTMIN+3", "SIGRTMIN+4",
+    "SIGRTMIN+5", "SIGRTMIN+6", "SIGRTMIN+7", "SIGRTMIN+8", "SIGRTMIN+9",
+    "SIGRTMIN+10", "SIGRTMIN+11", "SIGRTMIN+12", "SIGRTMIN+13", "SIGRTMIN+14",
+    "SIGRTMIN+15", "SIGRTMIN+16", "SIGRTMIN+17", "SIGRTMIN+18", "SIGRTMIN+19",
+    "SIGRTMIN+20", "SIGRTMIN+21", "SIGRTMIN+22", "SIGRTMIN+23", "SIGRTMIN+24",
+    "SIGRTMIN+25", "SIGRTMIN+26", "SIGRTMIN+27", "SIGRTMIN+28", "SIGRTMIN+29",
+    "SIGRTMIN+30", "SIGRTMIN+31", "SIGRTMIN+32",
+];
+
 impl AsRef<str> for Signal {
     fn as_ref(&self) -> &str {
         self.as_str()
@@ -190,11 +314,23 @@ impl AsRef<str> for Signal {
 
 impl fmt::Display for Signal {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        {
+            if self.is_realtime() && self.0 > libc::SIGRTMIN() {
+                return write!(f, "SIGRTMIN+{}", self.0 - libc::SIGRTMIN());
+            } else if self.is_realtime() && self.0 < libc::SIGRTMIN() {
+                return write!(f, "SIGRTMIN-{}", libc::SIGRTMIN() - self.0);
+            }
+        }
         f.write_str(self.as_ref())
     }
 }
 
-pub use self::Signal::*;
+impl fmt::Debug for Signal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
 
 #[cfg(target_os = "redox")]
 const SIGNALS: [Signal; 29] = [
@@ -350,12 +486,28 @@ impl Iterator for SignalIterator {
             self.next += 1;
             Some(next_signal)
         } else {
-            None
+            let next_signal = rt_signal(self.next - SIGNALS.len());
+            if next_signal.is_some() {
+                self.next += 1;
+            }
+            next_signal
         }
     }
 }
 
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn rt_signal(n: usize) -> Option<Signal> {
+    Signal::rt(n as libc::c_int).ok()
+}
+
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+fn rt_signal(_n: usize) -> Option<Signal> {
+    None
+}
+
 impl Signal {
+    /// Iterate over all the signals, followed by the real-time signals on
+    /// Linux and Android.
     pub fn iterator() -> SignalIterator {
         SignalIterator{next: 0}
     }
@@ -365,11 +517,17 @@ impl TryFrom<libc::c_int> for Signal {
     type Error = Error;
 
     fn try_from(signum: libc::c_int) -> Result<Signal> {
-        if 0 < signum && signum < NSIG {
-            Ok(unsafe { mem::transmute(signum) })
-        } else {
-            Err(Error::from(Errno::EINVAL))
+        let signal = Signal(signum);
+        if SIGNALS.contains(&signal) {
+            return Ok(signal);
         }
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        {
+            if signal.is_realtime() {
+                return Ok(signal);
+            }
+        }
+        Err(Error::from(Errno::EINVAL))
     }
 }
 
@@ -425,7 +583,7 @@ impl SigSet {
     }
 
     pub fn add(&mut self, signal: Signal) {
-        unsafe { libc::sigaddset(&mut self.sigset as *mut libc::sigset_t, signal as libc::c_int) };
+        unsafe { libc::sigaddset(&mut self.sigset as *mut libc::sigset_t, signal.as_raw()) };
     }
 
     pub fn clear(&mut self) {
@@ -433,11 +591,11 @@ impl SigSet {
     }
 
     pub fn remove(&mut self, signal: Signal) {
-        unsafe { libc::sigdelset(&mut self.sigset as *mut libc::sigset_t, signal as libc::c_int) };
+        unsafe { libc::sigdelset(&mut self.sigset as *mut libc::sigset_t, signal.as_raw()) };
     }
 
     pub fn contains(&self, signal: Signal) -> bool {
-        let res = unsafe { libc::sigismember(&self.sigset as *const libc::sigset_t, signal as libc::c_int) };
+        let res = unsafe { libc::sigismember(&self.sigset as *const libc::sigset_t, signal.as_raw()) };
 
         match res {
             1 => true,
@@ -612,7 +770,7 @@ impl SigAction {
 pub unsafe fn sigaction(signal: Signal, sigaction: &SigAction) -> Result<SigAction> {
     let mut oldact = mem::MaybeUninit::<libc::sigaction>::uninit();
 
-    let res = libc::sigaction(signal as libc::c_int,
+    let res = libc::sigaction(signal.as_raw(),
                               &sigaction.sigaction as *const libc::sigaction,
                               oldact.as_mut_ptr());
 
@@ -675,7 +833,7 @@ pub unsafe fn sigaction(signal: Signal, sigaction: &SigAction) -> Result<SigActi
 /// [SigActionStruct]: struct.SigAction.html
 /// [sigactionFn]: fn.sigaction.html
 pub unsafe fn signal(signal: Signal, handler: SigHandler) -> Result<SigHandler> {
-    let signal = signal as libc::c_int;
+    let signal = signal.as_raw();
     let res = match handler {
         SigHandler::SigDfl => libc::signal(signal, libc::SIG_DFL),
         SigHandler::SigIgn => libc::signal(signal, libc::SIG_IGN),
@@ -757,7 +915,7 @@ pub fn sigprocmask(how: SigmaskHow, set: Option<&SigSet>, oldset: Option<&mut Si
 pub fn kill<T: Into<Option<Signal>>>(pid: Pid, signal: T) -> Result<()> {
     let res = unsafe { libc::kill(pid.into(),
                                   match signal.into() {
-                                      Some(s) => s as libc::c_int,
+                                      Some(s) => s.as_raw(),
                                       None => 0,
                                   }) };
 
@@ -774,7 +932,7 @@ pub fn kill<T: Into<Option<Signal>>>(pid: Pid, signal: T) -> Result<()> {
 pub fn killpg<T: Into<Option<Signal>>>(pgrp: Pid, signal: T) -> Result<()> {
     let res = unsafe { libc::killpg(pgrp.into(),
                                   match signal.into() {
-                                      Some(s) => s as libc::c_int,
+                                      Some(s) => s.as_raw(),
                                       None => 0,
                                   }) };
 
@@ -782,11 +940,143 @@ pub fn killpg<T: Into<Option<Signal>>>(pgrp: Pid, signal: T) -> Result<()> {
 }
 
 pub fn raise(signal: Signal) -> Result<()> {
-    let res = unsafe { libc::raise(signal as libc::c_int) };
+    let res = unsafe { libc::raise(signal.as_raw()) };
 
     Errno::result(res).map(drop)
 }
 
+#[cfg(not(target_os = "redox"))]
+libc_bitflags!{
+    /// The state of the alternate signal stack of a thread.
+    pub struct SigStackFlags: libc::c_int {
+        /// The thread is currently executing on the alternate signal stack.
+        SS_ONSTACK;
+        /// The alternate signal stack is disabled.
+        SS_DISABLE;
+    }
+}
+
+/// An alternate signal stack, installed for the calling thread.
+///
+/// Signal handlers registered with `SA_ONSTACK` run on this stack instead of
+/// the thread's normal one, which lets them handle a `SIGSEGV` caused by a
+/// stack overflow.  The stack is allocated with
+/// [`mmap`](../mman/fn.mmap.html), with a guard page below it, so that a
+/// handler that overflows it faults instead of corrupting memory.
+///
+/// Dropping the `SignalStack` reinstalls the thread's previous alternate
+/// signal stack and frees the memory.  If another alternate signal stack was
+/// installed in the meantime, it is left in place and the memory is leaked
+/// instead, because that stack may reinstall this one when it goes away.  A
+/// `SignalStack` belongs to the thread that created it, so it can't be sent
+/// to another thread.
+///
+/// See also [sigaltstack(2)](https://man7.org/linux/man-pages/man2/sigaltstack.2.html).
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::signal::{SignalStack, SigStackFlags};
+/// let stack = SignalStack::new(64 * 1024).unwrap();
+/// assert_eq!(SignalStack::status().unwrap(), SigStackFlags::empty());
+/// drop(stack);
+/// ```
+#[cfg(not(target_os = "redox"))]
+#[derive(Debug)]
+pub struct SignalStack {
+    // The whole mapping, including the guard page
+    map: *mut libc::c_void,
+    map_len: usize,
+    guard_len: usize,
+    previous: libc::stack_t,
+}
+
+#[cfg(not(target_os = "redox"))]
+impl SignalStack {
+    /// Allocates a stack of at least `size` bytes and installs it as the
+    /// alternate signal stack of the calling thread.
+    ///
+    /// `size` is rounded up to a multiple of the page size.  It should be at
+    /// least `SIGSTKSZ`, plus whatever the handlers need.
+    pub fn new(size: usize) -> Result<SignalStack> {
+        use crate::sys::mman::{mmap, mprotect, munmap, MapFlags, ProtFlags};
+        use crate::unistd::{sysconf, SysconfVar};
+
+        let page = sysconf(SysconfVar::PAGE_SIZE)?
+            .map_or(4096, |page| page as usize);
+        let size = size.checked_add(page - 1)
+            .ok_or(Errno::EINVAL)? / page * page;
+        let map_len = size.checked_add(page).ok_or(Errno::EINVAL)?;
+
+        let map = unsafe {
+            mmap(ptr::null_mut(), map_len,
+                 ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+                 MapFlags::MAP_PRIVATE | MapFlags::MAP_ANON, -1, 0)?
+        };
+        let mut stack = unsafe { mem::zeroed::<libc::stack_t>() };
+        stack.ss_sp = unsafe { (map as *mut u8).add(page) } as *mut libc::c_void;
+        stack.ss_size = size;
+        stack.ss_flags = 0;
+        let mut previous = mem::MaybeUninit::<libc::stack_t>::uninit();
+        let res = unsafe { mprotect(map, page, ProtFlags::PROT_NONE) }
+            .and_then(|_| Errno::result(unsafe {
+                libc::sigaltstack(&stack, previous.as_mut_ptr())
+            }));
+        if let Err(e) = res {
+            let _ = unsafe { munmap(map, map_len) };
+            return Err(e);
+        }
+
+        Ok(SignalStack {
+            map,
+            map_len,
+            guard_len: page,
+            previous: unsafe { previous.assume_init() },
+        })
+    }
+
+    /// The usable size of the stack, not counting the guard page.
+    pub fn size(&self) -> usize {
+        self.map_len - self.guard_len
+    }
+
+    /// Returns the state of the calling thread's alternate signal stack.
+    pub fn status() -> Result<SigStackFlags> {
+        let mut current = mem::MaybeUninit::<libc::stack_t>::uninit();
+        Errno::result(unsafe {
+            libc::sigaltstack(ptr::null(), current.as_mut_ptr())
+        })?;
+        let current = unsafe { current.assume_init() };
+
+        Ok(SigStackFlags::from_bits_truncate(current.ss_flags))
+    }
+}
+
+#[cfg(not(target_os = "redox"))]
+impl Drop for SignalStack {
+    fn drop(&mut self) {
+        // If another stack was installed since, it may reinstall this one when
+        // it is dropped, so this stack must stay alive and is leaked.
+        let mut current = mem::MaybeUninit::<libc::stack_t>::uninit();
+        let res = unsafe { libc::sigaltstack(ptr::null(), current.as_mut_ptr()) };
+        if res != 0 {
+            return;
+        }
+        let current = unsafe { current.assume_init() };
+        let sp = unsafe { (self.map as *mut u8).add(self.guard_len) } as *mut libc::c_void;
+        if current.ss_sp != sp || current.ss_size != self.size() {
+            return;
+        }
+
+        // Reinstalling the previous stack fails with EPERM while a handler is
+        // running on this one.  The memory must be leaked in that case.
+        let res = unsafe { libc::sigaltstack(&self.previous, ptr::null_mut()) };
+        if res == 0 {
+            let _ = unsafe { crate::sys::mman::munmap(self.map, self.map_len) };
+        }
+    }
+}
+
 
 #[cfg(target_os = "freebsd")]
 pub type type_of_thread_id = libc::lwpid_t;
@@ -868,11 +1158,11 @@ mod sigevent {
                 SigevNotify::SigevThreadId{..} => 4  // No SIGEV_THREAD_ID defined
             };
             sev.sigev_signo = match sigev_notify {
-                SigevNotify::SigevSignal{ signal, .. } => signal as libc::c_int,
+                SigevNotify::SigevSignal{ signal, .. } => signal.as_raw(),
                 #[cfg(any(target_os = "dragonfly", target_os = "freebsd"))]
                 SigevNotify::SigevKevent{ kq, ..} => kq,
                 #[cfg(any(target_os = "linux", target_os = "freebsd"))]
-                SigevNotify::SigevThreadId{ signal, .. } => signal as libc::c_int,
+                SigevNotify::SigevThreadId{ signal, .. } => signal.as_raw(),
                 _ => 0
             };
             sev.sigev_value.sival_ptr = match sigev_notify {
@@ -911,6 +1201,803 @@ mod sigevent {
     }
 }
 
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod siginfo {
+    use crate::Result;
+    use crate::errno::Errno;
+    use crate::sys::time::TimeSpec;
+    use crate::sys::wait::WaitStatus;
+    use crate::unistd::{self, Pid, Uid};
+    use libc::{self, c_int, c_long, c_void};
+    use std::convert::TryFrom;
+    use std::mem;
+    use std::os::unix::io::RawFd;
+    use std::ptr;
+    use super::{SigSet, Signal};
+
+    // Values of si_code.  libc does not export these yet.
+    const SI_USER: c_int = 0;
+    const SI_QUEUE: c_int = -1;
+    const SI_TIMER: c_int = -2;
+    const SI_MESGQ: c_int = -3;
+    const SI_ASYNCIO: c_int = -4;
+    const SI_SIGIO: c_int = -5;
+    const SI_TKILL: c_int = -6;
+    const SYS_SECCOMP: c_int = 1;
+
+    // The kernel's layout of siginfo_t.  libc only exposes a few members of
+    // the union, and only on some architectures.
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct sifields_kill {
+        pid: libc::pid_t,
+        uid: libc::uid_t,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct sifields_timer {
+        tid: c_int,
+        overrun: c_int,
+        value: *mut c_void,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct sifields_rt {
+        pid: libc::pid_t,
+        uid: libc::uid_t,
+        value: *mut c_void,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct sifields_sigchld {
+        pid: libc::pid_t,
+        uid: libc::uid_t,
+        status: c_int,
+        utime: libc::clock_t,
+        stime: libc::clock_t,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct sifields_sigfault {
+        addr: *mut c_void,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct sifields_sigpoll {
+        band: c_long,
+        fd: c_int,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct sifields_sigsys {
+        call_addr: *mut c_void,
+        syscall: c_int,
+        arch: libc::c_uint,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    union sifields {
+        kill: sifields_kill,
+        timer: sifields_timer,
+        rt: sifields_rt,
+        sigchld: sifields_sigchld,
+        sigfault: sifields_sigfault,
+        sigpoll: sifields_sigpoll,
+        sigsys: sifields_sigsys,
+    }
+
+    #[repr(C)]
+    struct siginfo_f {
+        signo: c_int,
+        #[cfg(not(any(target_arch = "mips", target_arch = "mips64")))]
+        errno: c_int,
+        code: c_int,
+        #[cfg(any(target_arch = "mips", target_arch = "mips64"))]
+        errno: c_int,
+        fields: sifields,
+    }
+
+    /// Which member of the `siginfo_t` union is valid, based on `si_code`.
+    enum Class {
+        Kill,
+        Queue,
+        Timer,
+        Child,
+        Fault,
+        Poll,
+        Sys,
+        Kernel,
+    }
+
+    fn classify(signo: c_int, code: c_int) -> Class {
+        match code {
+            SI_USER | SI_TKILL => Class::Kill,
+            SI_QUEUE | SI_MESGQ | SI_ASYNCIO => Class::Queue,
+            SI_TIMER => Class::Timer,
+            SI_SIGIO => Class::Poll,
+            code if code > 0 => match signo {
+                libc::SIGCHLD => Class::Child,
+                libc::SIGSEGV | libc::SIGBUS | libc::SIGILL | libc::SIGFPE |
+                    libc::SIGTRAP => Class::Fault,
+                libc::SIGIO => Class::Poll,
+                libc::SIGSYS if code == SYS_SECCOMP => Class::Sys,
+                _ => Class::Kernel,
+            },
+            _ => Class::Kernel,
+        }
+    }
+
+    /// Where a signal came from, and the details that come with it.
+    ///
+    /// Which variant applies depends on the signal and on `si_code`; see
+    /// [sigaction(2)](https://man7.org/linux/man-pages/man2/sigaction.2.html).
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    pub enum SigOrigin {
+        /// Sent by [`kill`](fn.kill.html), [`raise`](fn.raise.html) or
+        /// `tgkill`.
+        Kill { pid: Pid, uid: Uid },
+        /// Sent by [`sigqueue`](fn.sigqueue.html), or on behalf of a POSIX
+        /// message queue or AIO request.  `value` is the `si_value` that was
+        /// queued along with the signal.
+        Queue { pid: Pid, uid: Uid, value: libc::intptr_t },
+        /// Expiration of a POSIX timer.  `overrun` is the number of
+        /// expirations that were missed.
+        Timer { timer_id: c_int, overrun: c_int, value: libc::intptr_t },
+        /// A child process changed state (`SIGCHLD`).  `status` is the exit
+        /// status if the `si_code` is `CLD_EXITED`, and the signal number
+        /// otherwise.
+        Child {
+            pid: Pid,
+            uid: Uid,
+            status: c_int,
+            utime: libc::clock_t,
+            stime: libc::clock_t,
+        },
+        /// A hardware fault (`SIGSEGV`, `SIGBUS`, `SIGILL`, `SIGFPE` or
+        /// `SIGTRAP`) at the address `addr`.
+        Fault { addr: *mut c_void },
+        /// An I/O event (`SIGIO`) on the file descriptor `fd`.
+        Poll { band: c_long, fd: RawFd },
+        /// A system call was blocked by a seccomp filter (`SIGSYS`).
+        Sys { call_addr: *mut c_void, syscall: c_int, arch: u32 },
+        /// Sent by the kernel, with no further details.
+        Kernel,
+    }
+
+    /// Information about a signal, decoded from a `siginfo_t`.
+    ///
+    /// Returned by [`sigwaitinfo`](fn.sigwaitinfo.html) and
+    /// [`sigtimedwait`](fn.sigtimedwait.html).  It can also be created from
+    /// the `siginfo_t` passed to a `SA_SIGINFO` handler, or from the
+    /// `signalfd_siginfo` returned by
+    /// [`SignalFd::read_signal`](../signalfd/struct.SignalFd.html#method.read_signal).
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    pub struct SigInfo {
+        signal: Signal,
+        errno: c_int,
+        code: c_int,
+        origin: SigOrigin,
+    }
+
+    impl SigInfo {
+        /// The signal that was delivered.
+        pub fn signal(&self) -> Signal {
+            self.signal
+        }
+
+        /// The error number associated with the signal, if any.
+        pub fn errno(&self) -> Option<Errno> {
+            match self.errno {
+                0 => None,
+                errno => Some(Errno::from_i32(errno)),
+            }
+        }
+
+        /// The raw `si_code`.
+        pub fn code(&self) -> c_int {
+            self.code
+        }
+
+        /// Where the signal came from.
+        pub fn origin(&self) -> SigOrigin {
+            self.origin
+        }
+
+        /// The process that sent the signal, or the child that changed
+        /// state.
+        pub fn pid(&self) -> Option<Pid> {
+            match self.origin {
+                SigOrigin::Kill { pid, .. } |
+                SigOrigin::Queue { pid, .. } |
+                SigOrigin::Child { pid, .. } => Some(pid),
+                _ => None,
+            }
+        }
+
+        /// The real user ID of the process that sent the signal.
+        pub fn uid(&self) -> Option<Uid> {
+            match self.origin {
+                SigOrigin::Kill { uid, .. } |
+                SigOrigin::Queue { uid, .. } |
+                SigOrigin::Child { uid, .. } => Some(uid),
+                _ => None,
+            }
+        }
+
+        /// The value sent along with a queued signal or a timer expiration.
+        pub fn value(&self) -> Option<libc::intptr_t> {
+            match self.origin {
+                SigOrigin::Queue { value, .. } |
+                SigOrigin::Timer { value, .. } => Some(value),
+                _ => None,
+            }
+        }
+
+        /// The address that caused a hardware fault.
+        pub fn fault_addr(&self) -> Option<*mut c_void> {
+            match self.origin {
+                SigOrigin::Fault { addr } => Some(addr),
+                _ => None,
+            }
+        }
+
+        /// The state change of a child, decoded from a `SIGCHLD` like
+        /// [`waitpid`](../wait/fn.waitpid.html) would report it.
+        pub fn child_status(&self) -> Option<WaitStatus> {
+            let (pid, status) = match self.origin {
+                SigOrigin::Child { pid, status, .. } => (pid, status),
+                _ => return None,
+            };
+            let signal = || Signal::try_from(status).ok();
+            match self.code {
+                libc::CLD_EXITED => Some(WaitStatus::Exited(pid, status)),
+                libc::CLD_KILLED => Some(WaitStatus::Signaled(pid, signal()?, false)),
+                libc::CLD_DUMPED => Some(WaitStatus::Signaled(pid, signal()?, true)),
+                libc::CLD_TRAPPED | libc::CLD_STOPPED =>
+                    Some(WaitStatus::Stopped(pid, signal()?)),
+                libc::CLD_CONTINUED => Some(WaitStatus::Continued(pid)),
+                _ => None,
+            }
+        }
+    }
+
+    impl TryFrom<&libc::siginfo_t> for SigInfo {
+        type Error = Errno;
+
+        /// Fails with `EINVAL` if the signal number is unknown.
+        fn try_from(info: &libc::siginfo_t) -> Result<Self> {
+            let info = unsafe {
+                &*(info as *const libc::siginfo_t as *const siginfo_f)
+            };
+            let signal = Signal::try_from(info.signo)?;
+            // Only the union member selected by classify is read, as the
+            // kernel does when copying siginfo to user space.
+            let origin = unsafe {
+                let f = &info.fields;
+                match classify(info.signo, info.code) {
+                    Class::Kill => SigOrigin::Kill {
+                        pid: Pid::from_raw(f.kill.pid),
+                        uid: Uid::from_raw(f.kill.uid),
+                    },
+                    Class::Queue => SigOrigin::Queue {
+                        pid: Pid::from_raw(f.rt.pid),
+                        uid: Uid::from_raw(f.rt.uid),
+                        value: f.rt.value as libc::intptr_t,
+                    },
+                    Class::Timer => SigOrigin::Timer {
+                        timer_id: f.timer.tid,
+                        overrun: f.timer.overrun,
+                        value: f.timer.value as libc::intptr_t,
+                    },
+                    Class::Child => SigOrigin::Child {
+                        pid: Pid::from_raw(f.sigchld.pid),
+                        uid: Uid::from_raw(f.sigchld.uid),
+                        status: f.sigchld.status,
+                        utime: f.sigchld.utime,
+                        stime: f.sigchld.stime,
+                    },
+                    Class::Fault => SigOrigin::Fault { addr: f.sigfault.addr },
+                    Class::Poll => SigOrigin::Poll {
+                        band: f.sigpoll.band,
+                        fd: f.sigpoll.fd,
+                    },
+                    Class::Sys => SigOrigin::Sys {
+                        call_addr: f.sigsys.call_addr,
+                        syscall: f.sigsys.syscall,
+                        arch: f.sigsys.arch,
+                    },
+                    Class::Kernel => SigOrigin::Kernel,
+                }
+            };
+            Ok(SigInfo { signal, errno: info.errno, code: info.code, origin })
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    impl TryFrom<&libc::signalfd_siginfo> for SigInfo {
+        type Error = Errno;
+
+        /// Fails with `EINVAL` if the signal number is unknown.
+        fn try_from(info: &libc::signalfd_siginfo) -> Result<Self> {
+            let signo = info.ssi_signo as c_int;
+            let signal = Signal::try_from(signo)?;
+            let pid = Pid::from_raw(info.ssi_pid as libc::pid_t);
+            let uid = Uid::from_raw(info.ssi_uid);
+            let origin = match classify(signo, info.ssi_code) {
+                Class::Kill => SigOrigin::Kill { pid, uid },
+                Class::Queue => SigOrigin::Queue {
+                    pid,
+                    uid,
+                    value: info.ssi_ptr as libc::intptr_t,
+                },
+                Class::Timer => SigOrigin::Timer {
+                    timer_id: info.ssi_tid as c_int,
+                    overrun: info.ssi_overrun as c_int,
+                    value: info.ssi_ptr as libc::intptr_t,
+                },
+                Class::Child => SigOrigin::Child {
+                    pid,
+                    uid,
+                    status: info.ssi_status,
+                    utime: info.ssi_utime as libc::clock_t,
+                    stime: info.ssi_stime as libc::clock_t,
+                },
+                Class::Fault => SigOrigin::Fault {
+                    addr: info.ssi_addr as usize as *mut c_void,
+                },
+                Class::Poll => SigOrigin::Poll {
+                    band: info.ssi_band as c_long,
+                    fd: info.ssi_fd,
+                },
+                Class::Sys => SigOrigin::Sys {
+                    call_addr: info.ssi_call_addr as usize as *mut c_void,
+                    syscall: info.ssi_syscall,
+                    arch: info.ssi_arch,
+                },
+                Class::Kernel => SigOrigin::Kernel,
+            };
+            Ok(SigInfo { signal, errno: info.ssi_errno, code: info.ssi_code, origin })
+        }
+    }
+
+    /// Builds the `siginfo_t` that `sigqueue` would send.
+    fn queue_info(signal: Signal, value: libc::intptr_t) -> libc::siginfo_t {
+        let mut info = unsafe { mem::zeroed::<libc::siginfo_t>() };
+        let f = unsafe {
+            &mut *(&mut info as *mut libc::siginfo_t as *mut siginfo_f)
+        };
+        f.signo = signal.as_raw();
+        f.code = SI_QUEUE;
+        f.fields.rt = sifields_rt {
+            pid: unistd::getpid().as_raw(),
+            uid: unistd::getuid().as_raw(),
+            value: value as *mut c_void,
+        };
+        info
+    }
+
+    /// Queues a signal and some data to a process.
+    ///
+    /// The receiver sees a [`SigOrigin::Queue`](enum.SigOrigin.html) whose
+    /// `value` is `value`.
+    ///
+    /// See also [sigqueue(3)](https://man7.org/linux/man-pages/man3/sigqueue.3.html).
+    pub fn sigqueue(pid: Pid, signal: Signal, value: libc::intptr_t) -> Result<()> {
+        let info = queue_info(signal, value);
+        let res = unsafe {
+            libc::syscall(libc::SYS_rt_sigqueueinfo, pid.as_raw(),
+                          signal.as_raw(), &info as *const libc::siginfo_t)
+        };
+
+        Errno::result(res).map(drop)
+    }
+
+    /// Queues a signal and some data to the thread `tid` of the thread group
+    /// `tgid`.
+    ///
+    /// See also [rt_tgsigqueueinfo(2)](https://man7.org/linux/man-pages/man2/rt_tgsigqueueinfo.2.html).
+    pub fn rt_tgsigqueueinfo(tgid: Pid, tid: Pid, signal: Signal,
+                             value: libc::intptr_t) -> Result<()>
+    {
+        let info = queue_info(signal, value);
+        let res = unsafe {
+            libc::syscall(libc::SYS_rt_tgsigqueueinfo, tgid.as_raw(),
+                          tid.as_raw(), signal.as_raw(),
+                          &info as *const libc::siginfo_t)
+        };
+
+        Errno::result(res).map(drop)
+    }
+
+    /// Suspends the calling thread until one of the signals in `set` is
+    /// pending, and accepts it.
+    ///
+    /// Unlike [`SigSet::wait`](struct.SigSet.html#method.wait), this returns
+    /// the details of the signal.  The signals in `set` should be blocked.
+    ///
+    /// See also [sigwaitinfo(2)](https://man7.org/linux/man-pages/man2/sigwaitinfo.2.html).
+    #[cfg(target_os = "linux")]
+    pub fn sigwaitinfo(set: &SigSet) -> Result<SigInfo> {
+        let mut info = mem::MaybeUninit::<libc::siginfo_t>::zeroed();
+        let res = unsafe { libc::sigwaitinfo(set.as_ref(), info.as_mut_ptr()) };
+
+        Errno::result(res)?;
+        SigInfo::try_from(unsafe { &info.assume_init() })
+    }
+
+    /// Like [`sigwaitinfo`](fn.sigwaitinfo.html), but gives up after
+    /// `timeout`, if any, with `EAGAIN`.
+    ///
+    /// See also [sigtimedwait(2)](https://man7.org/linux/man-pages/man2/sigtimedwait.2.html).
+    pub fn sigtimedwait(set: &SigSet, timeout: Option<TimeSpec>) -> Result<SigInfo> {
+        let mut info = mem::MaybeUninit::<libc::siginfo_t>::zeroed();
+        let timeout = timeout.as_ref()
+            .map_or(ptr::null(), |t| t.as_ref() as *const libc::timespec);
+        let res = unsafe {
+            libc::sigtimedwait(set.as_ref(), info.as_mut_ptr(), timeout)
+        };
+
+        Errno::result(res)?;
+        SigInfo::try_from(unsafe { &info.assume_init() })
+    }
+}
+
+#[cfg(not(target_os = "redox"))]
+mod signal_pipe {
+    use crate::Result;
+    use crate::errno::{self, Errno};
+    use crate::fcntl::OFlag;
+    use crate::unistd;
+    use libc::{self, c_int, c_void};
+    use std::collections::HashMap;
+    use std::convert::TryFrom;
+    use std::mem;
+    use std::os::unix::io::{AsRawFd, RawFd};
+    use std::ptr;
+    use std::sync::{Mutex, MutexGuard, Once};
+    use std::sync::atomic::{AtomicI32, AtomicPtr, AtomicUsize, Ordering};
+    use std::thread;
+    use super::{sigaction, SaFlags, SigAction, SigHandler, SigSet, Signal};
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    use super::SigInfo;
+
+    // The subscription of a pipe to a signal.  Nodes form a list that the
+    // signal handler walks without locking, so they are never freed; unused
+    // ones are recycled instead.
+    #[derive(Debug)]
+    struct Node {
+        signal: AtomicI32,
+        // 0 if the node is unused.  Otherwise the write end of the pipe plus
+        // one, shifted left by one, with the lowest bit set if the pipe wants
+        // whole siginfo_t records.
+        slot: AtomicUsize,
+        // Never changes once the node is in the list
+        next: *const Node,
+    }
+
+    unsafe impl Sync for Node {}
+
+    static HEAD: AtomicPtr<Node> = AtomicPtr::new(ptr::null_mut());
+    // The number of signal handlers currently walking the list
+    static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+
+    // For each signal with subscribers, how many there are and the action to
+    // restore after the last one leaves.
+    type Registry = HashMap<c_int, (usize, SigAction)>;
+
+    fn registry() -> MutexGuard<'static, Registry> {
+        static INIT: Once = Once::new();
+        static mut REGISTRY: *const Mutex<Registry> = ptr::null();
+        INIT.call_once(|| unsafe {
+            REGISTRY = Box::into_raw(Box::new(Mutex::new(HashMap::new())));
+        });
+        unsafe { &*REGISTRY }.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    #[cfg(any(target_os = "android",
+              target_os = "dragonfly",
+              target_os = "emscripten",
+              target_os = "freebsd",
+              target_os = "illumos",
+              target_os = "linux",
+              target_os = "netbsd",
+              target_os = "openbsd",
+              target_os = "solaris"))]
+    fn nonblocking_pipe() -> Result<(RawFd, RawFd)> {
+        unistd::pipe2(OFlag::O_CLOEXEC | OFlag::O_NONBLOCK)
+    }
+
+    #[cfg(not(any(target_os = "android",
+                  target_os = "dragonfly",
+                  target_os = "emscripten",
+                  target_os = "freebsd",
+                  target_os = "illumos",
+                  target_os = "linux",
+                  target_os = "netbsd",
+                  target_os = "openbsd",
+                  target_os = "solaris")))]
+    fn nonblocking_pipe() -> Result<(RawFd, RawFd)> {
+        use crate::fcntl::{fcntl, FcntlArg, FdFlag};
+
+        let (read_fd, write_fd) = unistd::pipe()?;
+        for &fd in &[read_fd, write_fd] {
+            let res = fcntl(fd, FcntlArg::F_SETFL(OFlag::O_NONBLOCK))
+                .and_then(|_| fcntl(fd, FcntlArg::F_SETFD(FdFlag::FD_CLOEXEC)));
+            if let Err(e) = res {
+                let _ = unistd::close(read_fd);
+                let _ = unistd::close(write_fd);
+                return Err(e);
+            }
+        }
+        Ok((read_fd, write_fd))
+    }
+
+    extern "C" fn handler(signo: c_int, info: *mut libc::siginfo_t, _: *mut c_void) {
+        let saved_errno = errno::errno();
+        IN_FLIGHT.fetch_add(1, Ordering::SeqCst);
+        let mut node = HEAD.load(Ordering::SeqCst) as *const Node;
+        while let Some(n) = unsafe { node.as_ref() } {
+            let slot = n.slot.load(Ordering::SeqCst);
+            if slot != 0 && n.signal.load(Ordering::SeqCst) == signo {
+                let fd = ((slot >> 1) - 1) as RawFd;
+                // If the pipe is full, the record is dropped, just like a
+                // signal that is already pending.
+                unsafe {
+                    if slot & 1 != 0 {
+                        libc::write(fd, info as *const c_void,
+                                    mem::size_of::<libc::siginfo_t>());
+                    } else {
+                        let byte = signo as u8;
+                        libc::write(fd, &byte as *const u8 as *const c_void, 1);
+                    }
+                }
+            }
+            node = n.next;
+        }
+        IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+        errno::set_errno(saved_errno);
+    }
+
+    fn subscribe(registry: &mut Registry, signal: Signal, slot: usize)
+        -> Result<&'static Node>
+    {
+        let signo = signal.as_raw();
+        let mut node = HEAD.load(Ordering::SeqCst) as *const Node;
+        let node = loop {
+            match unsafe { node.as_ref() } {
+                Some(n) if n.slot.load(Ordering::SeqCst) == 0 => break n,
+                Some(n) => node = n.next,
+                None => {
+                    let n: &'static Node = Box::leak(Box::new(Node {
+                        signal: AtomicI32::new(0),
+                        slot: AtomicUsize::new(0),
+                        next: HEAD.load(Ordering::SeqCst),
+                    }));
+                    HEAD.store(n as *const Node as *mut Node, Ordering::SeqCst);
+                    break n;
+                }
+            }
+        };
+        node.signal.store(signo, Ordering::SeqCst);
+        node.slot.store(slot, Ordering::SeqCst);
+
+        if let Some(entry) = registry.get_mut(&signo) {
+            entry.0 += 1;
+            return Ok(node);
+        }
+        let action = SigAction::new(SigHandler::SigAction(handler),
+                                    SaFlags::SA_SIGINFO | SaFlags::SA_RESTART,
+                                    SigSet::empty());
+        match unsafe { sigaction(signal, &action) } {
+            Ok(previous) => {
+                registry.insert(signo, (1, previous));
+                Ok(node)
+            }
+            Err(e) => {
+                node.slot.store(0, Ordering::SeqCst);
+                Err(e)
+            }
+        }
+    }
+
+    fn unsubscribe(registry: &mut Registry, node: &Node) {
+        let signo = node.signal.load(Ordering::SeqCst);
+        node.slot.store(0, Ordering::SeqCst);
+
+        let last = match registry.get_mut(&signo) {
+            Some(entry) => {
+                entry.0 -= 1;
+                entry.0 == 0
+            }
+            None => false,
+        };
+        if last {
+            let (_, previous) = registry.remove(&signo).unwrap();
+            if let Ok(signal) = Signal::try_from(signo) {
+                let _ = unsafe { sigaction(signal, &previous) };
+            }
+        }
+    }
+
+    /// Receives signals through a pipe, without writing a signal handler.
+    ///
+    /// A `SignalPipe` installs an internal handler for its signals, which
+    /// writes a record to a nonblocking pipe each time one of them is
+    /// caught.  The read end of the pipe can be watched with `poll`, `select`
+    /// or `epoll`, and the records read with [`read`](#method.read).  This is
+    /// known as the self-pipe trick.
+    ///
+    /// Several `SignalPipe`s may subscribe to the same signal, and each of
+    /// them receives it.  When the last one subscribed to a signal is
+    /// dropped, the action that was in place before the first one is
+    /// restored.  Any other change to the action of a subscribed signal, with
+    /// [`sigaction`](fn.sigaction.html) for example, breaks the delivery.
+    ///
+    /// Like any other signals, several instances of a standard signal may be
+    /// merged before the handler runs.  Records are also dropped when the
+    /// pipe is full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::sys::signal::{self, SignalPipe, SigSet};
+    /// let mut set = SigSet::empty();
+    /// set.add(signal::SIGUSR1);
+    /// let mut pipe = SignalPipe::new(&set).unwrap();
+    ///
+    /// signal::raise(signal::SIGUSR1).unwrap();
+    /// assert_eq!(pipe.read(), Ok(Some(signal::SIGUSR1)));
+    /// assert_eq!(pipe.read(), Ok(None));
+    /// ```
+    #[derive(Debug)]
+    pub struct SignalPipe {
+        read_fd: RawFd,
+        write_fd: RawFd,
+        siginfo: bool,
+        signals: SigSet,
+        nodes: Vec<&'static Node>,
+    }
+
+    impl SignalPipe {
+        /// Subscribes a new pipe to `signals`.
+        ///
+        /// Fails with `EINVAL` if `signals` contains `SIGKILL` or `SIGSTOP`,
+        /// which can't be caught, or `SIGSEGV`, `SIGBUS`, `SIGILL` or
+        /// `SIGFPE`, which would be raised again as soon as the handler
+        /// returns.
+        pub fn new(signals: &SigSet) -> Result<SignalPipe> {
+            SignalPipe::create(signals, false)
+        }
+
+        /// Subscribes a new pipe to `signals`, that receives the details of
+        /// each signal.  Use [`read_siginfo`](#method.read_siginfo) to read
+        /// them.
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        pub fn with_siginfo(signals: &SigSet) -> Result<SignalPipe> {
+            SignalPipe::create(signals, true)
+        }
+
+        fn create(signals: &SigSet, siginfo: bool) -> Result<SignalPipe> {
+            let signals_iter = || Signal::iterator().filter(|s| signals.contains(*s));
+            for signal in signals_iter() {
+                match signal {
+                    super::SIGKILL | super::SIGSTOP | super::SIGSEGV |
+                        super::SIGBUS | super::SIGILL | super::SIGFPE =>
+                        return Err(Errno::EINVAL),
+                    _ => (),
+                }
+            }
+
+            let (read_fd, write_fd) = nonblocking_pipe()?;
+            let mut pipe = SignalPipe {
+                read_fd,
+                write_fd,
+                siginfo,
+                signals: *signals,
+                nodes: Vec::new(),
+            };
+
+            let slot = ((write_fd as usize + 1) << 1) | siginfo as usize;
+            let mut registry = registry();
+            for signal in signals_iter() {
+                pipe.nodes.push(subscribe(&mut registry, signal, slot)?);
+            }
+            Ok(pipe)
+        }
+
+        /// The signals this pipe is subscribed to.
+        pub fn signals(&self) -> SigSet {
+            self.signals
+        }
+
+        // Reads one record, returning false if there is none.
+        fn read_record(&mut self, buf: *mut c_void, len: usize) -> Result<bool> {
+            let res = unsafe { libc::read(self.read_fd, buf, len) };
+            match Errno::result(res) {
+                Ok(n) if n as usize == len => Ok(true),
+                // Records are written atomically, so only someone else
+                // reading from the pipe can cause this
+                Ok(_) => Err(Errno::EIO),
+                Err(Errno::EAGAIN) => Ok(false),
+                Err(e) => Err(e),
+            }
+        }
+
+        /// Reads the next signal, or returns `None` if there is none.
+        pub fn read(&mut self) -> Result<Option<Signal>> {
+            let signo = if self.siginfo {
+                let mut info = mem::MaybeUninit::<libc::siginfo_t>::uninit();
+                if !self.read_record(info.as_mut_ptr() as *mut c_void,
+                                     mem::size_of::<libc::siginfo_t>())? {
+                    return Ok(None);
+                }
+                unsafe { info.assume_init() }.si_signo
+            } else {
+                let mut byte = 0u8;
+                if !self.read_record(&mut byte as *mut u8 as *mut c_void, 1)? {
+                    return Ok(None);
+                }
+                c_int::from(byte)
+            };
+            Signal::try_from(signo).map(Some)
+        }
+
+        /// Reads the details of the next signal, or returns `None` if there
+        /// is none.
+        ///
+        /// Fails with `EINVAL` unless the pipe was created with
+        /// [`with_siginfo`](#method.with_siginfo).
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        pub fn read_siginfo(&mut self) -> Result<Option<SigInfo>> {
+            if !self.siginfo {
+                return Err(Errno::EINVAL);
+            }
+            let mut info = mem::MaybeUninit::<libc::siginfo_t>::uninit();
+            if !self.read_record(info.as_mut_ptr() as *mut c_void,
+                                 mem::size_of::<libc::siginfo_t>())? {
+                return Ok(None);
+            }
+            SigInfo::try_from(unsafe { &info.assume_init() }).map(Some)
+        }
+    }
+
+    impl AsRawFd for SignalPipe {
+        fn as_raw_fd(&self) -> RawFd {
+            self.read_fd
+        }
+    }
+
+    impl Drop for SignalPipe {
+        fn drop(&mut self) {
+            {
+                let mut registry = registry();
+                for node in &self.nodes {
+                    unsubscribe(&mut registry, node);
+                }
+            }
+            // A handler that read a node before it was cleared may still be
+            // about to write to the pipe.  Handlers never block, so this only
+            // waits for the ones that are running to return.
+            while IN_FLIGHT.load(Ordering::SeqCst) != 0 {
+                thread::yield_now();
+            }
+
+            let _ = unistd::close(self.write_fd);
+            let e = unistd::close(self.read_fd);
+            if !thread::panicking() && e == Err(Errno::EBADF) {
+                panic!("Closing an invalid file descriptor!");
+            };
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(not(target_os = "redox"))]
@@ -1089,4 +2176,74 @@ mod tests {
             assert_eq!(mask.wait().unwrap(), SIGUSR1);
         }).join().unwrap();
     }
+
+    #[test]
+    fn test_try_from_invalid_value() {
+        assert!(Signal::try_from(0).is_err());
+        assert!(Signal::try_from(-1).is_err());
+        assert!(Signal::try_from(1024).is_err());
+        assert_eq!(Signal::try_from(libc::SIGINT), Ok(SIGINT));
+    }
+
+    #[test]
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn test_rt_signals() {
+        let (min, max) = (Signal::rtmin(), Signal::rtmax());
+        assert!(min < max);
+        assert!(min.is_realtime() && max.is_realtime() && !SIGUSR1.is_realtime());
+        assert_eq!(Signal::rt(0), Ok(min));
+        assert_eq!(Signal::rt(max.as_raw() - min.as_raw()), Ok(max));
+        assert!(Signal::rt(max.as_raw() - min.as_raw() + 1).is_err());
+        assert!(Signal::rt(-1).is_err());
+        assert_eq!(Signal::try_from(min.as_raw() + 1), Signal::rt(1));
+
+        let rt3 = Signal::rt(3).unwrap();
+        assert_eq!(rt3.to_string(), "SIGRTMIN+3");
+        assert_eq!(format!("{:?}", rt3), "SIGRTMIN+3");
+        assert_eq!(min.as_str(), "SIGRTMIN");
+        assert_eq!("SIGRTMIN+3".parse::<Signal>(), Ok(rt3));
+        assert_eq!("SIGRTMAX".parse::<Signal>(), Ok(max));
+        assert_eq!("SIGRTMAX-1".parse::<Signal>(), Signal::try_from(max.as_raw() - 1));
+        assert!("SIGRTMIN-99".parse::<Signal>().is_err());
+        assert!("SIGRTMAX+1".parse::<Signal>().is_err());
+        assert!("SIGRTMAX-99".parse::<Signal>().is_err());
+        assert!("SIGRTMINX".parse::<Signal>().is_err());
+
+        let rt: Vec<_> = Signal::iterator().filter(|s| s.is_realtime()).collect();
+        assert_eq!(rt.len() as libc::c_int, max.as_raw() - min.as_raw() + 1);
+        assert_eq!(rt.first(), Some(&min));
+        assert_eq!(rt.last(), Some(&max));
+    }
+
+    #[test]
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn test_reserved_rt_signals() {
+        // The C library keeps the first real-time signals of the kernel, but
+        // other programs can still send them
+        let min = Signal::rtmin().as_raw();
+        for signum in 32..min {
+            let signal = Signal::try_from(signum).unwrap();
+            assert!(signal.is_realtime());
+            let name = format!("SIGRTMIN-{}", min - signum);
+            assert_eq!(signal.as_str(), name);
+            assert_eq!(signal.to_string(), name);
+            assert_eq!(name.parse::<Signal>(), Ok(signal));
+        }
+        assert!(!SIGSYS.is_realtime());
+    }
+
+    #[test]
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn test_rt_sigwait() {
+        thread::spawn(|| {
+            let signal = Signal::rt(2).unwrap();
+            let mut mask = SigSet::empty();
+            mask.add(signal);
+            assert!(mask.contains(signal));
+            mask.thread_block().unwrap();
+
+            raise(signal).unwrap();
+            assert_eq!(mask.wait().unwrap(), signal);
+        }).join().unwrap();
+    }
 }
@@ -19,9 +19,10 @@ use libc;
 use crate::unistd;
 use crate::{Error, Result};
 use crate::errno::Errno;
-pub use crate::sys::signal::{self, SigSet};
+pub use crate::sys::signal::{self, SigInfo, SigSet};
 pub use libc::signalfd_siginfo as siginfo;
 
+use std::convert::TryFrom;
 use std::os::unix::io::{RawFd, AsRawFd};
 use std::mem;
 
@@ -87,6 +88,11 @@ impl SignalFd {
         Self::with_flags(mask, SfdFlags::empty())
     }
 
+    /// Returns a builder for a `SignalFd` accepting the signals of `mask`.
+    pub fn builder(mask: &SigSet) -> SignalFdBuilder {
+        SignalFdBuilder::new(mask)
+    }
+
     pub fn with_flags(mask: &SigSet, flags: SfdFlags) -> Result<SignalFd> {
         let fd = signalfd(SIGNALFD_NEW, mask, flags)?;
 
@@ -97,6 +103,11 @@ impl SignalFd {
         signalfd(self.0, mask, SfdFlags::empty()).map(drop)
     }
 
+    /// Reads a pending signal, or returns `None` if there is none and the
+    /// file descriptor is nonblocking.
+    ///
+    /// Use [`SigInfo::try_from`](../signal/struct.SigInfo.html) to decode
+    /// the result.
     pub fn read_signal(&mut self) -> Result<Option<siginfo>> {
         let mut buffer = mem::MaybeUninit::<[u8; SIGNALFD_SIGINFO_SIZE]>::uninit();
 
@@ -112,6 +123,165 @@ impl SignalFd {
             Err(error) => Err(error)
         }
     }
+
+    /// Like [`read_signal`](#method.read_signal), but decodes the signal.
+    pub fn read_siginfo(&mut self) -> Result<Option<SigInfo>> {
+        match self.read_signal()? {
+            Some(info) => SigInfo::try_from(&info).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads as many pending signals as fit in the spare capacity of `buf`
+    /// with a single system call, and appends them to it.
+    ///
+    /// Returns the number of signals read, which is 0 if there were none and
+    /// the file descriptor is nonblocking.  `buf` must have room for at least
+    /// one signal, or this fails with `EINVAL`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::sys::signalfd::*;
+    /// let mut mask = SigSet::empty();
+    /// mask.add(signal::SIGUSR1);
+    /// let mut sfd = SignalFd::builder(&mask)
+    ///     .flags(SfdFlags::SFD_NONBLOCK)
+    ///     .thread_block()
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// signal::raise(signal::SIGUSR1).unwrap();
+    /// let mut buf = Vec::with_capacity(16);
+    /// assert_eq!(sfd.read_signals(&mut buf), Ok(1));
+    /// assert_eq!(buf[0].ssi_signo, signal::SIGUSR1.as_raw() as u32);
+    /// ```
+    pub fn read_signals(&mut self, buf: &mut Vec<siginfo>) -> Result<usize> {
+        let len = buf.len();
+        let spare = buf.capacity() - len;
+
+        let res = Errno::result(unsafe {
+            libc::read(self.0,
+                       buf.as_mut_ptr().add(len) as *mut libc::c_void,
+                       spare * SIGNALFD_SIGINFO_SIZE)
+        }).map(|r| r as usize);
+        match res {
+            Ok(n) => {
+                assert_eq!(n % SIGNALFD_SIGINFO_SIZE, 0, "partial read on signalfd");
+                let n = n / SIGNALFD_SIGINFO_SIZE;
+                unsafe { buf.set_len(len + n) };
+                Ok(n)
+            },
+            Err(Errno::EAGAIN) => Ok(0),
+            Err(error) => Err(error)
+        }
+    }
+
+    /// Turns the `SignalFd` into an iterator over decoded signals, which
+    /// reads them in batches.
+    ///
+    /// The iterator ends when no signal is pending and the file descriptor is
+    /// nonblocking.
+    pub fn into_siginfos(self) -> SigInfos {
+        SigInfos {
+            sfd: self,
+            buf: Vec::with_capacity(SIGINFOS_BATCH),
+            pos: 0,
+        }
+    }
+}
+
+/// Creates a [`SignalFd`](struct.SignalFd.html), optionally blocking its
+/// signals in the calling thread first.
+///
+/// A signal must be blocked on every thread in a process, otherwise it won't
+/// be visible from signalfd.  Blocking them when creating the `SignalFd`
+/// covers the calling thread, and the threads it spawns afterwards.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SignalFdBuilder {
+    mask: SigSet,
+    flags: SfdFlags,
+    block: bool,
+}
+
+impl SignalFdBuilder {
+    /// Returns a builder for a `SignalFd` accepting the signals of `mask`.
+    pub fn new(mask: &SigSet) -> SignalFdBuilder {
+        SignalFdBuilder {
+            mask: *mask,
+            flags: SfdFlags::empty(),
+            block: false,
+        }
+    }
+
+    /// Sets the flags of the new file descriptor.
+    pub fn flags(mut self, flags: SfdFlags) -> SignalFdBuilder {
+        self.flags = flags;
+        self
+    }
+
+    /// Adds the signals of the mask to the signal mask of the calling thread
+    /// when building the `SignalFd`.
+    pub fn thread_block(mut self) -> SignalFdBuilder {
+        self.block = true;
+        self
+    }
+
+    /// Creates the `SignalFd`.
+    ///
+    /// If creating the file descriptor fails, the signal mask of the calling
+    /// thread is left unchanged.
+    pub fn build(self) -> Result<SignalFd> {
+        let oldmask = if self.block {
+            Some(self.mask.thread_swap_mask(signal::SigmaskHow::SIG_BLOCK)?)
+        } else {
+            None
+        };
+        let res = SignalFd::with_flags(&self.mask, self.flags);
+        if let (Err(_), Some(oldmask)) = (&res, oldmask) {
+            let _ = oldmask.thread_set_mask();
+        }
+        res
+    }
+}
+
+const SIGINFOS_BATCH: usize = 16;
+
+/// An owning iterator over the signals of a [`SignalFd`](struct.SignalFd.html).
+///
+/// Returned by [`SignalFd::into_siginfos`](struct.SignalFd.html#method.into_siginfos).
+#[derive(Debug)]
+pub struct SigInfos {
+    sfd: SignalFd,
+    buf: Vec<siginfo>,
+    pos: usize,
+}
+
+impl SigInfos {
+    /// Returns the underlying `SignalFd`, discarding the signals that were
+    /// read but not yet returned.
+    pub fn into_inner(self) -> SignalFd {
+        self.sfd
+    }
+}
+
+impl Iterator for SigInfos {
+    type Item = Result<SigInfo>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos == self.buf.len() {
+            self.buf.clear();
+            self.pos = 0;
+            match self.sfd.read_signals(&mut self.buf) {
+                Ok(0) => return None,
+                Ok(_) => (),
+                Err(error) => return Some(Err(error)),
+            }
+        }
+        let info = &self.buf[self.pos];
+        self.pos += 1;
+        Some(SigInfo::try_from(info))
+    }
 }
 
 impl Drop for SignalFd {
@@ -175,4 +345,47 @@ mod tests {
         let res = fd.read_signal();
         assert!(res.unwrap().is_none());
     }
+
+    #[test]
+    fn read_empty_signalfd_batch() {
+        let mask = SigSet::empty();
+        let mut fd = SignalFd::with_flags(&mask, SfdFlags::SFD_NONBLOCK).unwrap();
+
+        let mut buf = Vec::with_capacity(4);
+        assert_eq!(fd.read_signals(&mut buf), Ok(0));
+        assert!(buf.is_empty());
+        assert_eq!(fd.read_signals(&mut Vec::new()), Err(Errno::EINVAL));
+        assert_eq!(fd.into_siginfos().count(), 0);
+    }
+
+    #[test]
+    fn decode_siginfo() {
+        use crate::sys::wait::WaitStatus;
+        use crate::unistd::Pid;
+
+        let mut raw: siginfo = unsafe { mem::zeroed() };
+        raw.ssi_signo = libc::SIGCHLD as u32;
+        raw.ssi_code = libc::CLD_EXITED;
+        raw.ssi_pid = 42;
+        raw.ssi_status = 3;
+        let info = SigInfo::try_from(&raw).unwrap();
+        assert_eq!(info.signal(), signal::SIGCHLD);
+        assert_eq!(info.pid(), Some(Pid::from_raw(42)));
+        assert_eq!(info.child_status(), Some(WaitStatus::Exited(Pid::from_raw(42), 3)));
+
+        raw.ssi_code = libc::CLD_DUMPED;
+        raw.ssi_status = libc::SIGABRT;
+        let info = SigInfo::try_from(&raw).unwrap();
+        assert_eq!(info.child_status(),
+                   Some(WaitStatus::Signaled(Pid::from_raw(42), signal::SIGABRT, true)));
+
+        let mut raw: siginfo = unsafe { mem::zeroed() };
+        raw.ssi_signo = libc::SIGSEGV as u32;
+        raw.ssi_code = 1;   // SEGV_MAPERR
+        raw.ssi_addr = 0x1000;
+        let info = SigInfo::try_from(&raw).unwrap();
+        assert_eq!(info.fault_addr(), Some(0x1000 as *mut libc::c_void));
+        assert_eq!(info.pid(), None);
+        assert_eq!(info.child_status(), None);
+    }
 }
@@ -8,6 +8,8 @@ use std::hash::{Hash, Hasher};
 use std::path::Path;
 use std::os::unix::ffi::OsStrExt;
 #[cfg(any(target_os = "android", target_os = "linux"))]
+use std::ascii;
+#[cfg(any(target_os = "android", target_os = "linux"))]
 use crate::sys::socket::addr::netlink::NetlinkAddr;
 #[cfg(any(target_os = "android", target_os = "linux"))]
 use crate::sys::socket::addr::alg::AlgAddr;
@@ -542,6 +544,7 @@ impl UnixAddr {
                 ptr::copy_nonoverlapping(bytes.as_ptr(),
                                          ret.sun_path.as_mut_ptr() as *mut u8,
                                          bytes.len());
+                UnixAddr::set_sun_len(&mut ret, bytes.len());
 
                 Ok(UnixAddr(ret, bytes.len()))
             }
@@ -576,11 +579,68 @@ impl UnixAddr {
         }
     }
 
+    /// Create a new `sockaddr_un` representing an unnamed address.
+    ///
+    /// Unnamed addresses are reported for sockets that were never bound, for
+    /// example those created by [`socketpair`](../fn.socketpair.html).  On
+    /// Linux, binding a socket to an unnamed address autobinds it to a
+    /// unique name in the abstract namespace chosen by the kernel.
+    pub fn new_unnamed() -> UnixAddr {
+        let mut ret = libc::sockaddr_un {
+            sun_family: AddressFamily::Unix as sa_family_t,
+            .. unsafe { mem::zeroed() }
+        };
+        UnixAddr::set_sun_len(&mut ret, 0);
+
+        UnixAddr(ret, 0)
+    }
+
+    /// Sets `sun_len` to the total length of an address whose `sun_path` is
+    /// `pathlen` bytes long, on the platforms that have that field.
+    #[cfg(any(target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "macos",
+              target_os = "netbsd",
+              target_os = "openbsd"))]
+    fn set_sun_len(sun: &mut libc::sockaddr_un, pathlen: usize) {
+        sun.sun_len = (offset_of!(libc::sockaddr_un, sun_path) + pathlen) as u8;
+    }
+
+    #[cfg(not(any(target_os = "dragonfly",
+                  target_os = "freebsd",
+                  target_os = "ios",
+                  target_os = "macos",
+                  target_os = "netbsd",
+                  target_os = "openbsd")))]
+    fn set_sun_len(_sun: &mut libc::sockaddr_un, _pathlen: usize) {}
+
+    /// Returns the kind of this address: a filesystem path, a name in the
+    /// Linux abstract namespace, or unnamed.
+    pub fn kind(&self) -> UnixAddrKind<'_> {
+        if self.1 == 0 {
+            return UnixAddrKind::Unnamed;
+        }
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        {
+            if let Some(name) = self.as_abstract() {
+                return UnixAddrKind::Abstract(name);
+            }
+        }
+        match self.path() {
+            Some(path) => UnixAddrKind::Pathname(path),
+            None => UnixAddrKind::Unnamed,
+        }
+    }
+
     fn sun_path(&self) -> &[u8] {
         unsafe { slice::from_raw_parts(self.0.sun_path.as_ptr() as *const u8, self.1) }
     }
 
     /// If this address represents a filesystem path, return that path.
+    ///
+    /// `None` is returned for both unnamed and abstract addresses; use
+    /// [`kind`](#method.kind) to tell them apart.
     pub fn path(&self) -> Option<&Path> {
         if self.1 == 0 || self.0.sun_path[0] == 0 {
             // unnamed or abstract
@@ -612,15 +672,36 @@ impl UnixAddr {
     }
 }
 
+/// The kind of a [`UnixAddr`](struct.UnixAddr.html), as returned by
+/// [`UnixAddr::kind`](struct.UnixAddr.html#method.kind).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum UnixAddrKind<'a> {
+    /// A filesystem path
+    Pathname(&'a Path),
+    /// A name in the Linux abstract namespace, without the leading null byte
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    Abstract(&'a [u8]),
+    /// An unnamed address, as reported for unbound sockets
+    Unnamed,
+}
+
 impl fmt::Display for UnixAddr {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self.1 == 0 {
-            f.write_str("<unbound UNIX socket>")
-        } else if let Some(path) = self.path() {
-            path.display().fmt(f)
-        } else {
-            let display = String::from_utf8_lossy(&self.sun_path()[1..]);
-            write!(f, "@{}", display)
+        match self.kind() {
+            UnixAddrKind::Pathname(path) => path.display().fmt(f),
+            // Abstract names are arbitrary bytes and often contain nulls, so
+            // escape them the way `ss` and `netstat` would.
+            #[cfg(any(target_os = "android", target_os = "linux"))]
+            UnixAddrKind::Abstract(name) => {
+                f.write_str("@")?;
+                for &b in name {
+                    for c in ascii::escape_default(b) {
+                        fmt::Write::write_char(f, c as char)?;
+                    }
+                }
+                Ok(())
+            }
+            UnixAddrKind::Unnamed => f.write_str("<unbound UNIX socket>"),
         }
     }
 }
@@ -1257,6 +1338,18 @@ pub mod vsock {
     /// The address for AF_VSOCK socket is defined as a combination of a
     /// 32-bit Context Identifier (CID) and a 32-bit port number.
     impl VsockAddr {
+        /// Wildcard CID, to bind to any CID of the local host
+        pub const CID_ANY: u32 = libc::VMADDR_CID_ANY;
+        /// CID of the hypervisor
+        pub const CID_HYPERVISOR: u32 = libc::VMADDR_CID_HYPERVISOR;
+        /// CID for communication within the local host, using the
+        /// `vsock_loopback` transport (since Linux 5.6)
+        pub const CID_LOCAL: u32 = libc::VMADDR_CID_LOCAL;
+        /// CID of the host, as seen from a guest
+        pub const CID_HOST: u32 = libc::VMADDR_CID_HOST;
+        /// Wildcard port, to bind to an unused port chosen by the kernel
+        pub const PORT_ANY: u32 = libc::VMADDR_PORT_ANY;
+
         pub fn new(cid: u32, port: u32) -> VsockAddr {
             let mut addr: sockaddr_vm = unsafe { mem::zeroed() };
             addr.svm_family = AddressFamily::Vsock as sa_family_t;
@@ -1266,6 +1359,21 @@ pub mod vsock {
             VsockAddr(addr)
         }
 
+        /// Creates an address with the wildcard CID `VMADDR_CID_ANY`
+        pub fn any(port: u32) -> VsockAddr {
+            VsockAddr::new(VsockAddr::CID_ANY, port)
+        }
+
+        /// Creates an address with the local loopback CID `VMADDR_CID_LOCAL`
+        pub fn local(port: u32) -> VsockAddr {
+            VsockAddr::new(VsockAddr::CID_LOCAL, port)
+        }
+
+        /// Creates an address with the host CID `VMADDR_CID_HOST`
+        pub fn host(port: u32) -> VsockAddr {
+            VsockAddr::new(VsockAddr::CID_HOST, port)
+        }
+
         /// Context Identifier (CID)
         pub fn cid(&self) -> u32 {
             self.0.svm_cid
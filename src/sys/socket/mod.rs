@@ -28,6 +28,7 @@ pub use self::addr::{
     SockAddr,
     InetAddr,
     UnixAddr,
+    UnixAddrKind,
     IpAddr,
     Ipv4Addr,
     Ipv6Addr,
@@ -39,6 +40,7 @@ pub use self::addr::{
     SockAddr,
     InetAddr,
     UnixAddr,
+    UnixAddrKind,
     IpAddr,
     Ipv4Addr,
     Ipv6Addr,
@@ -198,21 +200,21 @@ libc_bitflags!{
     }
 }
 
-libc_bitflags!{
+bitflags::bitflags!{
     /// Flags for send/recv and their relatives
     pub struct MsgFlags: c_int {
         /// Sends or requests out-of-band data on sockets that support this notion
         /// (e.g., of type [`Stream`](enum.SockType.html)); the underlying protocol must also
         /// support out-of-band data.
-        MSG_OOB;
+        const MSG_OOB = libc::MSG_OOB;
         /// Peeks at an incoming message. The data is treated as unread and the next
         /// [`recv()`](fn.recv.html)
         /// or similar function shall still return this data.
-        MSG_PEEK;
+        const MSG_PEEK = libc::MSG_PEEK;
         /// Receive operation blocks until the full amount of data can be
         /// returned. The function may return smaller amount of data if a signal
         /// is caught, an error or disconnect occurs.
-        MSG_WAITALL;
+        const MSG_WAITALL = libc::MSG_WAITALL;
         /// Enables nonblocking operation; if the operation would block,
         /// `EAGAIN` or `EWOULDBLOCK` is returned.  This provides similar
         /// behavior to setting the `O_NONBLOCK` flag
@@ -223,9 +225,9 @@ libc_bitflags!{
         /// which will affect all threads in
         /// the calling process and as well as other processes that hold
         /// file descriptors referring to the same open file description.
-        MSG_DONTWAIT;
+        const MSG_DONTWAIT = libc::MSG_DONTWAIT;
         /// Receive flags: Control Data was discarded (buffer too small)
-        MSG_CTRUNC;
+        const MSG_CTRUNC = libc::MSG_CTRUNC;
         /// For raw ([`Packet`](addr/enum.AddressFamily.html)), Internet datagram
         /// (since Linux 2.4.27/2.6.8),
         /// netlink (since Linux 2.6.22) and UNIX datagram (since Linux 3.4)
@@ -234,15 +236,15 @@ libc_bitflags!{
         /// domain ([unix(7)](https://linux.die.net/man/7/unix)) sockets.
         ///
         /// For use with Internet stream sockets, see [tcp(7)](https://linux.die.net/man/7/tcp).
-        MSG_TRUNC;
+        const MSG_TRUNC = libc::MSG_TRUNC;
         /// Terminates a record (when this notion is supported, as for
         /// sockets of type [`SeqPacket`](enum.SockType.html)).
-        MSG_EOR;
+        const MSG_EOR = libc::MSG_EOR;
         /// This flag specifies that queued errors should be received from
         /// the socket error queue. (For more details, see
         /// [recvfrom(2)](https://linux.die.net/man/2/recvfrom))
         #[cfg(any(target_os = "android", target_os = "linux"))]
-        MSG_ERRQUEUE;
+        const MSG_ERRQUEUE = libc::MSG_ERRQUEUE;
         /// Set the `close-on-exec` flag for the file descriptor received via a UNIX domain
         /// file descriptor using the `SCM_RIGHTS` operation (described in
         /// [unix(7)](https://linux.die.net/man/7/unix)).
@@ -256,10 +258,27 @@ libc_bitflags!{
                   target_os = "linux",
                   target_os = "netbsd",
                   target_os = "openbsd"))]
-        MSG_CMSG_CLOEXEC;
+        const MSG_CMSG_CLOEXEC = libc::MSG_CMSG_CLOEXEC;
+        /// Send the data without copying it into the kernel, if possible.  The
+        /// buffer must not be modified until the kernel reports that it is done
+        /// with it, through a
+        /// [`ControlMessageOwned::ZeroCopyCompletion`](enum.ControlMessageOwned.html#variant.ZeroCopyCompletion)
+        /// read from the socket's error queue.  Requires the
+        /// [`ZeroCopy`](sockopt/struct.ZeroCopy.html) socket option.
+        ///
+        /// [Further reading](https://www.kernel.org/doc/html/latest/networking/msg_zerocopy.html)
+        // libc does not export MSG_ZEROCOPY yet
+        #[cfg(target_os = "linux")]
+        const MSG_ZEROCOPY = 0x400_0000;
     }
 }
 
+// Constants from linux/errqueue.h that libc does not export yet
+#[cfg(target_os = "linux")]
+const SO_EE_ORIGIN_ZEROCOPY: u8 = 5;
+#[cfg(target_os = "linux")]
+const SO_EE_CODE_ZEROCOPY_COPIED: u8 = 1;
+
 cfg_if! {
     if #[cfg(any(target_os = "android", target_os = "linux"))] {
         /// Unix credentials of the sending process.
@@ -356,6 +375,46 @@ cfg_if! {
     }
 }
 
+/// Completion notification for a range of `MSG_ZEROCOPY` sends.
+///
+/// The kernel numbers every successful send with
+/// [`MsgFlags::MSG_ZEROCOPY`](struct.MsgFlags.html#associatedconstant.MSG_ZEROCOPY)
+/// on a socket, starting from zero.  Once it no longer needs the buffers of
+/// some consecutive sends, it queues a notification covering their numbers
+/// on the socket's error queue.  Read it with
+/// [`recvmsg`](fn.recvmsg.html) and `MsgFlags::MSG_ERRQUEUE`.
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ZeroCopyCompletion {
+    lo: u32,
+    hi: u32,
+    copied: bool,
+}
+
+#[cfg(target_os = "linux")]
+impl ZeroCopyCompletion {
+    /// The number of the first completed send
+    pub fn lo(&self) -> u32 {
+        self.lo
+    }
+
+    /// The number of the last completed send (inclusive)
+    pub fn hi(&self) -> u32 {
+        self.hi
+    }
+
+    /// The range of completed sends
+    pub fn range(&self) -> std::ops::RangeInclusive<u32> {
+        self.lo..=self.hi
+    }
+
+    /// Whether the kernel fell back to copying the data, as it always does
+    /// on loopback.  If so, `MSG_ZEROCOPY` is only overhead for this socket.
+    pub fn copied(&self) -> bool {
+        self.copied
+    }
+}
+
 /// Request for multicast socket operations
 ///
 /// This is a wrapper type around `ip_mreq`.
@@ -392,6 +451,97 @@ impl Ipv6MembershipRequest {
     }
 }
 
+/// Queue selected by the [`TcpRepairQueue`](sockopt/struct.TcpRepairQueue.html)
+/// socket option on a TCP socket in repair mode.
+#[cfg(target_os = "linux")]
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TcpRepairQueue {
+    /// No queue is selected.
+    NoQueue = 0,
+    /// The receive queue: data that has arrived from the peer but has not
+    /// been read yet.
+    RecvQueue = 1,
+    /// The send queue: data that has been written but not acknowledged by
+    /// the peer yet.
+    SendQueue = 2,
+}
+
+/// Option negotiated during the TCP handshake, as restored by the
+/// [`TcpRepairOptions`](sockopt/struct.TcpRepairOptions.html) socket option.
+///
+/// This is a wrapper type around `struct tcp_repair_opt`.
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct TcpRepairOpt {
+    opt_code: u32,
+    opt_val: u32,
+}
+
+#[cfg(target_os = "linux")]
+impl TcpRepairOpt {
+    // Option kinds from linux/tcp.h that libc does not export yet
+    const TCPOPT_MSS: u32 = 2;
+    const TCPOPT_WINDOW: u32 = 3;
+    const TCPOPT_SACK_PERM: u32 = 4;
+    const TCPOPT_TIMESTAMP: u32 = 8;
+
+    /// The maximum segment size announced by the peer.
+    pub fn mss(mss: u32) -> Self {
+        TcpRepairOpt { opt_code: Self::TCPOPT_MSS, opt_val: mss }
+    }
+
+    /// The window scale factors for sending and receiving.
+    pub fn window(snd_wscale: u8, rcv_wscale: u8) -> Self {
+        TcpRepairOpt {
+            opt_code: Self::TCPOPT_WINDOW,
+            opt_val: u32::from(snd_wscale) | u32::from(rcv_wscale) << 16,
+        }
+    }
+
+    /// Selective acknowledgements are enabled.
+    pub fn sack_permitted() -> Self {
+        TcpRepairOpt { opt_code: Self::TCPOPT_SACK_PERM, opt_val: 0 }
+    }
+
+    /// Timestamps are enabled.
+    pub fn timestamp() -> Self {
+        TcpRepairOpt { opt_code: Self::TCPOPT_TIMESTAMP, opt_val: 0 }
+    }
+}
+
+/// Window state of a TCP socket in repair mode, as used by the
+/// [`TcpRepairWindow`](sockopt/struct.TcpRepairWindow.html) socket option.
+///
+/// This is `struct tcp_repair_window`, which libc does not export yet.
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct TcpRepairWindow {
+    /// Sequence number of the segment that last updated the send window.
+    pub snd_wl1: u32,
+    /// Send window.
+    pub snd_wnd: u32,
+    /// Largest send window announced by the peer.
+    pub max_window: u32,
+    /// Receive window.
+    pub rcv_wnd: u32,
+    /// Sequence number at the time of the last receive window update.
+    pub rcv_wup: u32,
+}
+
+/// Contents of one queue of a TCP socket, as saved by
+/// [`tcp_repair_dump_queue`](fn.tcp_repair_dump_queue.html).
+#[cfg(target_os = "linux")]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct TcpQueueState {
+    /// Sequence number of the first byte of `data`.
+    pub seq: u32,
+    /// Bytes in the queue.
+    pub data: Vec<u8>,
+}
+
 /// Create a buffer large enough for storing some control messages as returned
 /// by [`recvmsg`](fn.recvmsg.html).
 ///
@@ -621,6 +771,17 @@ pub enum ControlMessageOwned {
     #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
     RxqOvfl(u32),
 
+    /// Completion notification for `MSG_ZEROCOPY` sends, read from the
+    /// error queue of an IPv4 or IPv6 socket with `MsgFlags::MSG_ERRQUEUE`.
+    ///
+    /// The control message buffer must be large enough to hold a
+    /// `sock_extended_err` followed by a socket address, for example
+    /// `cmsg_space!(libc::sock_extended_err, libc::sockaddr_in6)`.
+    ///
+    /// [Further reading](https://www.kernel.org/doc/html/latest/networking/msg_zerocopy.html)
+    #[cfg(target_os = "linux")]
+    ZeroCopyCompletion(ZeroCopyCompletion),
+
     /// Catch-all variant for unimplemented cmsg types.
     #[doc(hidden)]
     Unknown(UnknownCmsg),
@@ -724,6 +885,19 @@ impl ControlMessageOwned {
                 let drop_counter = ptr::read_unaligned(p as *const u32);
                 ControlMessageOwned::RxqOvfl(drop_counter)
             },
+            #[cfg(target_os = "linux")]
+            (libc::SOL_IP, libc::IP_RECVERR) | (libc::SOL_IPV6, libc::IPV6_RECVERR)
+                if len >= mem::size_of::<libc::sock_extended_err>() &&
+                    ptr::read_unaligned(p as *const libc::sock_extended_err)
+                        .ee_origin == SO_EE_ORIGIN_ZEROCOPY =>
+            {
+                let ee = ptr::read_unaligned(p as *const libc::sock_extended_err);
+                ControlMessageOwned::ZeroCopyCompletion(ZeroCopyCompletion {
+                    lo: ee.ee_info,
+                    hi: ee.ee_data,
+                    copied: ee.ee_code & SO_EE_CODE_ZEROCOPY_COPIED != 0,
+                })
+            },
             (_, _) => {
                 let sl = slice::from_raw_parts(p, len);
                 let ucmsg = UnknownCmsg(*header, Vec::<u8>::from(sl));
@@ -1692,6 +1866,72 @@ pub fn setsockopt<O: SetSockOpt>(fd: RawFd, opt: O, val: &O::Val) -> Result<()>
     opt.set(fd, val)
 }
 
+/// Save the contents of one queue of a TCP socket in repair mode.
+///
+/// The socket must have been put in repair mode with
+/// [`TcpRepair`](sockopt/struct.TcpRepair.html) first.  This leaves `queue`
+/// selected with [`TcpRepairQueue`](sockopt/struct.TcpRepairQueue.html).
+///
+/// [Further reading](https://lwn.net/Articles/495304/)
+#[cfg(target_os = "linux")]
+pub fn tcp_repair_dump_queue(fd: RawFd, queue: TcpRepairQueue) -> Result<TcpQueueState> {
+    setsockopt(fd, sockopt::TcpRepairQueue, &queue)?;
+    let end = getsockopt(fd, sockopt::TcpQueueSeq)?;
+
+    // SIOCINQ and SIOCOUTQ, which libc does not export, share their values
+    // with FIONREAD and TIOCOUTQ.
+    let request = match queue {
+        TcpRepairQueue::SendQueue => libc::TIOCOUTQ,
+        _ => libc::FIONREAD,
+    };
+    let mut len: c_int = 0;
+    let res = unsafe { libc::ioctl(fd, request, &mut len) };
+    Errno::result(res)?;
+
+    let mut data = vec![0; len as usize];
+    if !data.is_empty() {
+        let n = recv(fd, &mut data, MsgFlags::MSG_PEEK | MsgFlags::MSG_DONTWAIT)?;
+        data.truncate(n);
+    }
+    Ok(TcpQueueState {
+        seq: end.wrapping_sub(data.len() as u32),
+        data,
+    })
+}
+
+/// Restore the contents of one queue of a TCP socket in repair mode.
+///
+/// `fd` must be a new socket in repair mode.  This sets the sequence number of
+/// `queue`, so it must be called before `fd` is connected; the data is written
+/// by [`tcp_repair_restore_queue_data`](fn.tcp_repair_restore_queue_data.html)
+/// once it is.
+///
+/// [Further reading](https://lwn.net/Articles/495304/)
+#[cfg(target_os = "linux")]
+pub fn tcp_repair_restore_queue_seq(fd: RawFd, queue: TcpRepairQueue, state: &TcpQueueState)
+    -> Result<()>
+{
+    setsockopt(fd, sockopt::TcpRepairQueue, &queue)?;
+    setsockopt(fd, sockopt::TcpQueueSeq, &state.seq)
+}
+
+/// Write the data saved by [`tcp_repair_dump_queue`](fn.tcp_repair_dump_queue.html)
+/// back into one queue of a connected TCP socket in repair mode.
+///
+/// [Further reading](https://lwn.net/Articles/495304/)
+#[cfg(target_os = "linux")]
+pub fn tcp_repair_restore_queue_data(fd: RawFd, queue: TcpRepairQueue, state: &TcpQueueState)
+    -> Result<()>
+{
+    setsockopt(fd, sockopt::TcpRepairQueue, &queue)?;
+    let mut data = &state.data[..];
+    while !data.is_empty() {
+        let n = send(fd, data, MsgFlags::empty())?;
+        data = &data[n..];
+    }
+    Ok(())
+}
+
 /// Get the address of the peer connected to the socket `fd`.
 ///
 /// [Further reading](https://pubs.opengroup.org/onlinepubs/9699919799/functions/getpeername.html)
@@ -1765,10 +2005,18 @@ pub fn sockaddr_storage_to_addr(
             Ok(SockAddr::Inet(InetAddr::V6(sin6)))
         }
         libc::AF_UNIX => {
-            let pathlen = len - offset_of!(sockaddr_un, sun_path);
+            let mut pathlen = len - offset_of!(sockaddr_un, sun_path);
             let sun = unsafe {
                 *(addr as *const _ as *const sockaddr_un)
             };
+            // Linux includes the terminating null of filesystem paths in the
+            // length.  Drop it so that the result compares equal to the
+            // `UnixAddr` that was bound.
+            if pathlen > 0 && sun.sun_path[0] != 0 {
+                pathlen = unsafe {
+                    libc::strnlen(sun.sun_path.as_ptr(), pathlen)
+                };
+            }
             Ok(SockAddr::Unix(UnixAddr(sun, pathlen)))
         }
         #[cfg(any(target_os = "android", target_os = "linux"))]
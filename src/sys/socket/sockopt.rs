@@ -3,6 +3,8 @@ use super::{GetSockOpt, SetSockOpt};
 use crate::Result;
 use crate::errno::Errno;
 use crate::sys::time::TimeVal;
+#[cfg(target_os = "linux")]
+use crate::unistd::Gid;
 use libc::{self, c_int, c_void, socklen_t};
 use std::mem::{
     self,
@@ -17,6 +19,17 @@ use std::os::unix::ffi::OsStrExt;
 // TCP_CA_NAME_MAX isn't defined in user space include files
 #[cfg(any(target_os = "freebsd", target_os = "linux"))] 
 const TCP_CA_NAME_MAX: usize = 16;
+// Options from linux/vm_sockets.h that libc does not export yet
+#[cfg(any(target_os = "android", target_os = "linux"))]
+const SO_VM_SOCKETS_BUFFER_SIZE: c_int = 0;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+const SO_VM_SOCKETS_BUFFER_MIN_SIZE: c_int = 1;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+const SO_VM_SOCKETS_BUFFER_MAX_SIZE: c_int = 2;
+// This is SO_VM_SOCKETS_CONNECT_TIMEOUT_OLD, which takes a `timeval` with the
+// same layout as libc's.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+const SO_VM_SOCKETS_CONNECT_TIMEOUT: c_int = 6;
 
 /// Helper for implementing `SetSockOpt` for a given socket option. See
 /// [`::sys::socket::SetSockOpt`](sys/socket/trait.SetSockOpt.html).
@@ -263,6 +276,14 @@ sockopt_impl!(Both, TcpKeepCount, libc::IPPROTO_TCP, libc::TCP_KEEPCNT, u32);
 sockopt_impl!(Both, TcpKeepInterval, libc::IPPROTO_TCP, libc::TCP_KEEPINTVL, u32);
 #[cfg(any(target_os = "fuchsia", target_os = "linux"))]
 sockopt_impl!(Both, TcpUserTimeout, libc::IPPROTO_TCP, libc::TCP_USER_TIMEOUT, u32);
+#[cfg(target_os = "linux")]
+sockopt_impl!(Both, TcpRepair, libc::IPPROTO_TCP, libc::TCP_REPAIR, bool);
+#[cfg(target_os = "linux")]
+sockopt_impl!(Both, TcpRepairQueue, libc::IPPROTO_TCP, libc::TCP_REPAIR_QUEUE, super::TcpRepairQueue);
+#[cfg(target_os = "linux")]
+sockopt_impl!(Both, TcpQueueSeq, libc::IPPROTO_TCP, libc::TCP_QUEUE_SEQ, u32);
+#[cfg(target_os = "linux")]
+sockopt_impl!(Both, TcpRepairWindow, libc::IPPROTO_TCP, libc::TCP_REPAIR_WINDOW, super::TcpRepairWindow);
 sockopt_impl!(Both, RcvBuf, libc::SOL_SOCKET, libc::SO_RCVBUF, usize);
 sockopt_impl!(Both, SndBuf, libc::SOL_SOCKET, libc::SO_SNDBUF, usize);
 #[cfg(any(target_os = "android", target_os = "linux"))]
@@ -330,8 +351,76 @@ sockopt_impl!(Both, UdpGsoSegment, libc::SOL_UDP, libc::UDP_SEGMENT, libc::c_int
 sockopt_impl!(Both, UdpGroSegment, libc::IPPROTO_UDP, libc::UDP_GRO, bool);
 #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
 sockopt_impl!(Both, RxqOvfl, libc::SOL_SOCKET, libc::SO_RXQ_OVFL, libc::c_int);
+#[cfg(target_os = "linux")]
+sockopt_impl!(Both, ZeroCopy, libc::SOL_SOCKET, libc::SO_ZEROCOPY, bool);
+#[cfg(any(target_os = "android", target_os = "linux"))]
+sockopt_impl!(Both, VsockBufferSize, libc::AF_VSOCK, SO_VM_SOCKETS_BUFFER_SIZE, u64);
+#[cfg(any(target_os = "android", target_os = "linux"))]
+sockopt_impl!(Both, VsockBufferMinSize, libc::AF_VSOCK, SO_VM_SOCKETS_BUFFER_MIN_SIZE, u64);
+#[cfg(any(target_os = "android", target_os = "linux"))]
+sockopt_impl!(Both, VsockBufferMaxSize, libc::AF_VSOCK, SO_VM_SOCKETS_BUFFER_MAX_SIZE, u64);
+#[cfg(any(target_os = "android", target_os = "linux"))]
+sockopt_impl!(Both, VsockConnectTimeout, libc::AF_VSOCK, SO_VM_SOCKETS_CONNECT_TIMEOUT, TimeVal);
 sockopt_impl!(Both, Ipv6V6Only, libc::IPPROTO_IPV6, libc::IPV6_V6ONLY, bool);
 
+/// The supplementary groups of the peer of a UNIX domain socket, as they
+/// were when it called `connect` or `socketpair`.  Complements
+/// [`PeerCredentials`](struct.PeerCredentials.html).
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct PeerGroups;
+
+#[cfg(target_os = "linux")]
+impl GetSockOpt for PeerGroups {
+    type Val = Vec<Gid>;
+
+    fn get(&self, fd: RawFd) -> Result<Vec<Gid>> {
+        let mut groups = Vec::<Gid>::with_capacity(16);
+        loop {
+            let mut len = (groups.capacity() * mem::size_of::<Gid>()) as socklen_t;
+            // On the platforms we currently support, `Gid` has the same
+            // representation in memory as a bare `gid_t`.
+            let res = unsafe {
+                libc::getsockopt(fd, libc::SOL_SOCKET, libc::SO_PEERGROUPS,
+                                 groups.as_mut_ptr() as *mut c_void, &mut len)
+            };
+            match Errno::result(res) {
+                Ok(_) => {
+                    unsafe { groups.set_len(len as usize / mem::size_of::<Gid>()) };
+                    return Ok(groups);
+                },
+                // The kernel stores the required size in `len`
+                Err(Errno::ERANGE) => {
+                    groups.reserve(len as usize / mem::size_of::<Gid>());
+                },
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Restores the options negotiated during the TCP handshake on a socket in
+/// repair mode.  The socket must already be connected.
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct TcpRepairOptions;
+
+#[cfg(target_os = "linux")]
+impl SetSockOpt for TcpRepairOptions {
+    type Val = Vec<super::TcpRepairOpt>;
+
+    fn set(&self, fd: RawFd, val: &Vec<super::TcpRepairOpt>) -> Result<()> {
+        unsafe {
+            let res = libc::setsockopt(fd,
+                                       libc::IPPROTO_TCP,
+                                       libc::TCP_REPAIR_OPTIONS,
+                                       val.as_ptr() as *const c_void,
+                                       (val.len() * mem::size_of::<super::TcpRepairOpt>()) as socklen_t);
+            Errno::result(res).map(drop)
+        }
+    }
+}
+
 #[cfg(any(target_os = "android", target_os = "linux"))]
 #[derive(Copy, Clone, Debug)]
 pub struct AlgSetAeadAuthSize;
@@ -664,6 +753,19 @@ mod test {
         assert!(a_cred.pid() != 0);
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn can_get_peergroups_on_unix_socket() {
+        use super::super::*;
+        use crate::unistd::{close, getgroups};
+
+        let (a, b) = socketpair(AddressFamily::Unix, SockType::Stream, None, SockFlag::empty()).unwrap();
+        let a_groups = getsockopt(a, super::PeerGroups).unwrap();
+        assert_eq!(a_groups, getgroups().unwrap());
+        close(a).unwrap();
+        close(b).unwrap();
+    }
+
     #[test]
     fn is_socket_type_unix() {
         use super::super::*;
@@ -0,0 +1,123 @@
+//! POSIX per-process timers.
+//!
+//! A [`Timer`](struct.Timer.html) measures time with a [`ClockId`] and
+//! notifies its expirations as described by a [`SigEvent`]: by sending a
+//! signal to the process or to a specific thread, or not at all.  Timers are
+//! armed with the same [`Expiration`] as
+//! [`TimerFd`](../timerfd/struct.TimerFd.html).
+//!
+//! For more documentation, please read
+//! [timer_create(2)](https://man7.org/linux/man-pages/man2/timer_create.2.html).
+//!
+//! # Examples
+//!
+//! Receive a `SIGALRM` in the calling thread after 10 milliseconds.
+//! ```
+//! # use nix::sys::signal::{self, SigEvent, SigevNotify, SigSet};
+//! # use nix::sys::time::{TimeSpec, TimeValLike};
+//! # use nix::sys::timer::{Expiration, Timer, TimerSetTimeFlags};
+//! # use nix::time::ClockId;
+//! # use nix::unistd::gettid;
+//! #
+//! let mut mask = SigSet::empty();
+//! mask.add(signal::SIGALRM);
+//! mask.thread_block().unwrap();
+//!
+//! let sigevent = SigEvent::new(SigevNotify::SigevThreadId {
+//!     signal: signal::SIGALRM,
+//!     thread_id: gettid().as_raw(),
+//!     si_value: 0,
+//! });
+//! let mut timer = Timer::new(ClockId::CLOCK_MONOTONIC, sigevent).unwrap();
+//! timer.set(
+//!     Expiration::OneShot(TimeSpec::milliseconds(10)),
+//!     TimerSetTimeFlags::empty()
+//! ).unwrap();
+//!
+//! assert_eq!(mask.wait(), Ok(signal::SIGALRM));
+//! ```
+use crate::sys::signal::SigEvent;
+use crate::sys::timerfd::TimerSpec;
+use crate::time::ClockId;
+use crate::{errno::Errno, Result};
+use bitflags::bitflags;
+use std::mem;
+use std::ptr;
+
+pub use crate::sys::timerfd::Expiration;
+
+bitflags! {
+    /// Flags that are used for arming the timer.
+    pub struct TimerSetTimeFlags: libc::c_int {
+        const TIMER_ABSTIME = libc::TIMER_ABSTIME;
+    }
+}
+
+/// A POSIX per-process timer.  It is deleted on drop.
+#[derive(Debug)]
+pub struct Timer(libc::timer_t);
+
+impl Timer {
+    /// Creates a new timer based on the clock defined by `clockid`, that
+    /// notifies its expirations as described by `sigevent`.
+    ///
+    /// The timer is created disarmed.
+    pub fn new(clockid: ClockId, sigevent: SigEvent) -> Result<Self> {
+        let mut timer_id = mem::MaybeUninit::<libc::timer_t>::uninit();
+        let mut sigevent = sigevent.sigevent();
+        Errno::result(unsafe {
+            libc::timer_create(clockid.as_raw(), &mut sigevent, timer_id.as_mut_ptr())
+        })
+        .map(|_| Timer(unsafe { timer_id.assume_init() }))
+    }
+
+    /// Sets a new alarm on the timer, replacing any previous one.
+    ///
+    /// The alarm is interpreted as for
+    /// [`TimerFd::set`](../timerfd/struct.TimerFd.html#method.set).  With
+    /// `TIMER_ABSTIME`, the initial expiration is an absolute time of the
+    /// timer's clock.  Setting a one shot alarm with a 0s `TimeSpec` disarms
+    /// the timer.
+    pub fn set(&mut self, expiration: Expiration, flags: TimerSetTimeFlags) -> Result<()> {
+        let timerspec: TimerSpec = expiration.into();
+        Errno::result(unsafe {
+            libc::timer_settime(self.0, flags.bits(), timerspec.as_ref(), ptr::null_mut())
+        })
+        .map(drop)
+    }
+
+    /// Get the parameters for the alarm currently set, if any.
+    ///
+    /// The initial expiration is always relative to the current time.
+    pub fn get(&self) -> Result<Option<Expiration>> {
+        let mut timerspec = TimerSpec::none();
+        Errno::result(unsafe { libc::timer_gettime(self.0, timerspec.as_mut()) }).map(|_| {
+            let value = timerspec.as_ref().it_value;
+            if value.tv_sec == 0 && value.tv_nsec == 0 {
+                None
+            } else {
+                Some(timerspec.into())
+            }
+        })
+    }
+
+    /// Returns the number of expirations that happened while the signal of
+    /// the last expiration was still pending.
+    ///
+    /// Only one signal per timer can be pending at a time, so this count is
+    /// how the receiver learns about the expirations it missed.
+    pub fn overruns(&self) -> Result<libc::c_int> {
+        Errno::result(unsafe { libc::timer_getoverrun(self.0) })
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        if !std::thread::panicking() {
+            let result = Errno::result(unsafe { libc::timer_delete(self.0) });
+            if let Err(Errno::EINVAL) = result {
+                panic!("timer_delete of Timer encountered EINVAL");
+            }
+        }
+    }
+}
@@ -84,10 +84,10 @@ bitflags! {
 }
 
 #[derive(Debug, Clone, Copy)]
-struct TimerSpec(libc::itimerspec);
+pub(crate) struct TimerSpec(libc::itimerspec);
 
 impl TimerSpec {
-    pub fn none() -> Self {
+    pub(crate) fn none() -> Self {
         Self(libc::itimerspec {
             it_interval: libc::timespec {
                 tv_sec: 0,
@@ -107,6 +107,12 @@ impl AsRef<libc::itimerspec> for TimerSpec {
     }
 }
 
+impl AsMut<libc::itimerspec> for TimerSpec {
+    fn as_mut(&mut self) -> &mut libc::itimerspec {
+        &mut self.0
+    }
+}
+
 impl From<Expiration> for TimerSpec {
     fn from(expiration: Expiration) -> TimerSpec {
         match expiration {
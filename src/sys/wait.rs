@@ -56,9 +56,9 @@ libc_bitflags!(
 /// in a child process `Pid`, such as the process exiting or stopping,
 /// plus additional data about the transition if any.
 ///
-/// Note that there are two Linux-specific enum variants, `PtraceEvent`
-/// and `PtraceSyscall`. Portable code should avoid exhaustively
-/// matching on `WaitStatus`.
+/// Note that there are three Linux-specific enum variants, `PtraceEvent`,
+/// `PtraceSyscall` and `PtraceGroupStop`. Portable code should avoid
+/// exhaustively matching on `WaitStatus`.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum WaitStatus {
     /// The process exited normally (as with `exit()` or returning from
@@ -91,6 +91,17 @@ pub enum WaitStatus {
     /// [`ptrace`(2)]: https://man7.org/linux/man-pages/man2/ptrace.2.html
     #[cfg(any(target_os = "linux", target_os = "android"))]
     PtraceSyscall(Pid),
+    /// The traced process entered a group-stop because of the given stop
+    /// signal, and was attached with `PTRACE_SEIZE`. Without
+    /// `PTRACE_SEIZE`, a group-stop is reported as `Stopped`. This
+    /// matches a `PTRACE_EVENT_STOP` with one of `SIGSTOP`, `SIGTSTP`,
+    /// `SIGTTIN` or `SIGTTOU`; the other `PTRACE_EVENT_STOP`s, such as the
+    /// ones caused by `PTRACE_INTERRUPT`, are reported as `PtraceEvent`
+    /// with `SIGTRAP`. See [`ptrace`(2)] for more information.
+    ///
+    /// [`ptrace`(2)]: https://man7.org/linux/man-pages/man2/ptrace.2.html
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    PtraceGroupStop(Pid, Signal),
     /// The process was previously stopped but has resumed execution
     /// after receiving a `SIGCONT` signal. This is only reported if
     /// `WaitPidFlag::WCONTINUED` was passed. This case matches the C
@@ -111,7 +122,7 @@ impl WaitStatus {
             Exited(p, _) | Signaled(p, _, _) | Stopped(p, _) | Continued(p) => Some(p),
             StillAlive => None,
             #[cfg(any(target_os = "android", target_os = "linux"))]
-            PtraceEvent(p, _, _) | PtraceSyscall(p) => Some(p),
+            PtraceEvent(p, _, _) | PtraceSyscall(p) | PtraceGroupStop(p, _) => Some(p),
         }
     }
 }
@@ -153,6 +164,14 @@ fn syscall_stop(status: i32) -> bool {
     libc::WSTOPSIG(status) == libc::SIGTRAP | 0x80
 }
 
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn group_stop_signal(status: i32) -> bool {
+    match libc::WSTOPSIG(status) {
+        libc::SIGSTOP | libc::SIGTSTP | libc::SIGTTIN | libc::SIGTTOU => true,
+        _ => false,
+    }
+}
+
 #[cfg(any(target_os = "android", target_os = "linux"))]
 fn stop_additional(status: i32) -> c_int {
     (status >> 16) as c_int
@@ -194,6 +213,10 @@ impl WaitStatus {
                             WaitStatus::PtraceSyscall(pid)
                         } else if status_additional == 0 {
                             WaitStatus::Stopped(pid, stop_signal(status)?)
+                        } else if status_additional == libc::PTRACE_EVENT_STOP &&
+                            group_stop_signal(status)
+                        {
+                            WaitStatus::PtraceGroupStop(pid, stop_signal(status)?)
                         } else {
                             WaitStatus::PtraceEvent(pid, stop_signal(status)?,
                                                     stop_additional(status))
@@ -1007,6 +1007,24 @@ pub fn write(fd: RawFd, buf: &[u8]) -> Result<usize> {
     Errno::result(res).map(|r| r as usize)
 }
 
+/// Read from `fd` until end of file, retrying on `EINTR`.
+///
+/// This is meant for small files such as the ones of procfs, sysfs or
+/// cgroupfs, which are generated as they are read.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn read_to_end(fd: RawFd) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        match read(fd, &mut buf) {
+            Ok(0) => return Ok(data),
+            Ok(n) => data.extend_from_slice(&buf[..n]),
+            Err(Errno::EINTR) => (),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
 /// Directive that tells [`lseek`] and [`lseek64`] what the offset is relative to.
 ///
 /// [`lseek`]: ./fn.lseek.html
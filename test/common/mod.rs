@@ -14,19 +14,21 @@ use cfg_if::cfg_if;
 cfg_if! {
     if #[cfg(any(target_os = "android", target_os = "linux"))] {
         #[macro_export] macro_rules! require_capability {
-            ($capname:ident) => {
-                use ::caps::{Capability, CapSet, has_cap};
-
-                if !has_cap(None, CapSet::Effective, Capability::$capname)
-                    .unwrap()
-                {
-                    skip!("Insufficient capabilities. Skipping test.");
-                }
+            ($($capname:ident),+) => {
+                use nix::sys::capability::{capget, Capability};
+                use nix::unistd::Pid;
+
+                let effective = capget(Pid::from_raw(0)).unwrap().effective;
+                $(
+                    if !effective.contains(Capability::$capname) {
+                        skip!("Insufficient capabilities. Skipping test.");
+                    }
+                )+
             }
         }
     } else if #[cfg(not(target_os = "redox"))] {
         #[macro_export] macro_rules! require_capability {
-            ($capname:ident) => {}
+            ($($capname:ident),+) => {}
         }
     }
 }
@@ -11,6 +11,14 @@ mod test_signal;
           target_os = "macos",
           target_os = "netbsd"))]
 mod test_aio;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod test_capability;
+#[cfg(all(target_os = "linux",
+          any(target_arch = "aarch64",
+              target_arch = "arm",
+              target_arch = "x86",
+              target_arch = "x86_64")))]
+mod test_seccomp;
 #[cfg(target_os = "linux")]
 mod test_signalfd;
 #[cfg(not(target_os = "redox"))]
@@ -43,3 +51,5 @@ mod test_pthread;
 mod test_ptrace;
 #[cfg(target_os = "linux")]
 mod test_timerfd;
+#[cfg(target_os = "linux")]
+mod test_timer;
@@ -49,7 +49,7 @@ fn test_accessors() {
     assert_eq!(2, aiocb.offset());
     assert_eq!(42, aiocb.priority());
     let sev = aiocb.sigevent().sigevent();
-    assert_eq!(Signal::SIGUSR2 as i32, sev.sigev_signo);
+    assert_eq!(Signal::SIGUSR2.as_raw(), sev.sigev_signo);
     assert_eq!(99, sev.sigev_value.sival_ptr as i64);
 }
 
@@ -0,0 +1,85 @@
+use nix::sys::capability::*;
+use nix::unistd::Pid;
+use std::thread;
+
+use crate::*;
+
+#[test]
+fn test_capability_names() {
+    assert_eq!(Capability::CAP_SYS_ADMIN.to_string(), "CAP_SYS_ADMIN");
+    assert_eq!("cap_net_raw".parse::<Capability>().unwrap(), Capability::CAP_NET_RAW);
+    assert!("CAP_UNKNOWN".parse::<Capability>().is_err());
+    for (i, cap) in Capability::iterator().enumerate() {
+        assert_eq!(Capability::from_c_int(i as i32).unwrap(), cap);
+    }
+    assert!(Capability::from_c_int(-1).is_err());
+    assert!(cap_last_cap().unwrap() >= Capability::CAP_AUDIT_READ as i32);
+}
+
+#[test]
+fn test_capset_ops() {
+    let mut set = CapSet::empty();
+    assert!(set.is_empty());
+    set.add(Capability::CAP_CHOWN);
+    set.add(Capability::CAP_CHECKPOINT_RESTORE);
+    assert_eq!(set.bits(), 1 | 1 << 40);
+    assert!(set.contains(Capability::CAP_CHECKPOINT_RESTORE));
+    set.remove(Capability::CAP_CHOWN);
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec![Capability::CAP_CHECKPOINT_RESTORE]);
+    set.extend([Capability::CAP_KILL].iter().cloned().collect());
+    assert_eq!(set.iter().count(), 2);
+    assert_eq!(CapSet::all().iter().count(), Capability::iterator().count());
+    set.clear();
+    assert!(set.is_empty());
+}
+
+#[test]
+fn test_capget() {
+    let caps = capget(Pid::from_raw(0)).unwrap();
+    // The effective set is always a subset of the permitted set
+    assert_eq!(caps.effective.bits() & !caps.permitted.bits(), 0);
+    assert_eq!(capget(nix::unistd::gettid()).unwrap(), caps);
+    get_securebits().unwrap();
+}
+
+// Capabilities belong to threads, so change them in a separate thread to
+// keep other tests unaffected.
+#[test]
+fn test_capset() {
+    require_capability!(CAP_SETPCAP);
+
+    // Container runtimes drop many capabilities from the bounding set, so
+    // only use the ones that are still there
+    let this = Pid::from_raw(0);
+    let usable: Vec<Capability> = capget(this).unwrap().permitted.iter()
+        .filter(|&cap| cap != Capability::CAP_SETPCAP)
+        .filter(|&cap| bounding_is_set(cap).unwrap_or(false))
+        .collect();
+    if usable.len() < 2 {
+        skip!("Not enough capabilities in the bounding set. Skipping test.");
+    }
+    let (dropped, ambient) = (usable[0], usable[1]);
+
+    thread::spawn(move || {
+        bounding_drop(dropped).unwrap();
+        assert!(!bounding_is_set(dropped).unwrap());
+
+        let mut caps = capget(this).unwrap();
+        caps.inheritable.add(ambient);
+        caps.effective.remove(dropped);
+        capset(this, &caps).unwrap();
+        assert_eq!(capget(this).unwrap(), caps);
+
+        ambient_raise(ambient).unwrap();
+        assert!(ambient_is_set(ambient).unwrap());
+        ambient_lower(ambient).unwrap();
+        assert!(!ambient_is_set(ambient).unwrap());
+        ambient_raise(ambient).unwrap();
+        ambient_clear_all().unwrap();
+        assert!(!ambient_is_set(ambient).unwrap());
+
+        set_securebits(SecureBits::SECBIT_NO_CAP_AMBIENT_RAISE).unwrap();
+        assert_eq!(get_securebits().unwrap(), SecureBits::SECBIT_NO_CAP_AMBIENT_RAISE);
+        assert!(ambient_raise(ambient).is_err());
+    }).join().unwrap();
+}
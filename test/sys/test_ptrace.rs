@@ -175,3 +175,280 @@ fn test_ptrace_syscall() {
         },
     }
 }
+
+#[cfg(all(target_os = "linux",
+          any(all(target_arch = "x86_64",
+                  any(target_env = "gnu", target_env = "musl")),
+              all(target_arch = "x86", target_env = "gnu"),
+              target_arch = "aarch64",
+              target_arch = "arm",
+              target_arch = "riscv64")))]
+#[test]
+fn test_ptrace_registers() {
+    use nix::sys::signal::{kill, Signal};
+    use nix::sys::ptrace::{Registers, RegisterSet};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+
+    require_capability!(CAP_SYS_PTRACE);
+
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    match unsafe{fork()}.expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            let pid = getpid();
+            kill(pid, Signal::SIGSTOP).unwrap();
+            // The tracer turns this into kill(pid, 0)
+            kill(pid, Signal::SIGKILL).unwrap();
+            unsafe { ::libc::_exit(0); }
+        },
+
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, Signal::SIGSTOP)));
+            ptrace::setoptions(child, Options::PTRACE_O_TRACESYSGOOD).unwrap();
+
+            // kill entry
+            ptrace::syscall(child, None).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::PtraceSyscall(child)));
+            let mut regs = Registers::get(child).unwrap();
+            assert_eq!(regs.syscall_number(), ::libc::SYS_kill as i64);
+            assert_eq!(regs.syscall_arg(0), child.as_raw() as u64);
+            assert_eq!(regs.syscall_arg(1), Signal::SIGKILL.as_raw() as u64);
+            assert_ne!(regs.instruction_pointer(), 0);
+            assert_ne!(regs.stack_pointer(), 0);
+            regs.set_syscall_arg(1, 0);
+            regs.set(child).unwrap();
+
+            let mut raw = [0u8; mem::size_of::<ptrace::RawRegisters>()];
+            assert_eq!(ptrace::getregset(child, RegisterSet::NT_PRSTATUS, &mut raw),
+                       Ok(raw.len()));
+            ptrace::setregset(child, RegisterSet::NT_PRSTATUS, &raw).unwrap();
+
+            // kill exit
+            ptrace::syscall(child, None).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::PtraceSyscall(child)));
+            let regs = Registers::get(child).unwrap();
+            assert_eq!(regs.return_value(), 0);
+
+            ptrace::cont(child, None).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        },
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn test_ptrace_tracer() {
+    use nix::sys::signal::{raise, Signal};
+    use nix::sys::ptrace::{TraceEvent, Tracer};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+
+    require_capability!(CAP_SYS_PTRACE);
+
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    match unsafe{fork()}.expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            raise(Signal::SIGSTOP).unwrap();
+            match unsafe { fork() } {
+                Ok(Child) => unsafe { ::libc::_exit(0) },
+                Ok(Parent { child }) => { let _ = waitpid(child, None); },
+                Err(_) => unsafe { ::libc::_exit(1) },
+            }
+            getpid();
+            unsafe { ::libc::_exit(5); }
+        },
+
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, Signal::SIGSTOP)));
+            let tracer = Tracer::new(child, Options::PTRACE_O_EXITKILL).unwrap();
+            let events = tracer.collect::<Result<Vec<_>, _>>().unwrap();
+
+            let grandchild = events.iter().find_map(|event| match *event {
+                TraceEvent::Fork { pid, child: grandchild } if pid == child =>
+                    Some(grandchild),
+                TraceEvent::Clone { pid, child: grandchild } if pid == child =>
+                    Some(grandchild),
+                _ => None,
+            }).expect("no fork event");
+            assert!(events.iter().any(|event| match *event {
+                TraceEvent::SyscallEntry { pid, nr, .. } =>
+                    pid == child && nr == ::libc::SYS_getpid as u64,
+                _ => false,
+            }));
+            assert!(events.contains(&TraceEvent::Exited { pid: grandchild, status: 0 }));
+            assert!(events.contains(&TraceEvent::Exiting { pid: child, status: 5 << 8 }));
+            assert_eq!(events.last(), Some(&TraceEvent::Exited { pid: child, status: 5 }));
+        },
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn test_ptrace_read_write_bytes() {
+    use nix::sys::mman::{mmap, mprotect, munmap, MapFlags, ProtFlags};
+    use nix::sys::ptrace::AddressType;
+    use nix::sys::signal::{raise, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+    use std::ffi::CString;
+    use std::ptr;
+
+    require_capability!(CAP_SYS_PTRACE);
+
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    // A read-only page, that is only writable through ptrace
+    let len = 4096;
+    let page = unsafe {
+        mmap(ptr::null_mut(), len, ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+             MapFlags::MAP_PRIVATE | MapFlags::MAP_ANONYMOUS, -1, 0).unwrap()
+    };
+    let bytes = unsafe { std::slice::from_raw_parts_mut(page as *mut u8, len) };
+    bytes[..6].copy_from_slice(b"hello\0");
+    // A string and an array of pointers that end at the end of the page
+    let string = len - 32;
+    bytes[string..string + 8].copy_from_slice(b"string\0\0");
+    let array = len - mem::size_of::<usize>() * 2;
+    let strings = [page as usize + string, 0];
+    for (i, p) in strings.iter().enumerate() {
+        let offset = array + i * mem::size_of::<usize>();
+        bytes[offset..offset + mem::size_of::<usize>()].copy_from_slice(&p.to_ne_bytes());
+    }
+    unsafe { mprotect(page, len, ProtFlags::PROT_READ).unwrap() };
+
+    match unsafe{fork()}.expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            raise(Signal::SIGSTOP).unwrap();
+            unsafe { ::libc::_exit(0); }
+        },
+
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, Signal::SIGSTOP)));
+            let at = |offset: usize| (page as usize + offset) as AddressType;
+
+            let mut buf = [0u8; 5];
+            ptrace::read_bytes(child, at(1), &mut buf).unwrap();
+            assert_eq!(&buf, b"ello\0");
+
+            unsafe { ptrace::write_bytes(child, at(1), b"ELLO") }.unwrap();
+            let mut buf = [0u8; 6];
+            ptrace::read_bytes(child, at(0), &mut buf).unwrap();
+            assert_eq!(&buf, b"hELLO\0");
+            assert_eq!(ptrace::read_cstring(child, at(0)),
+                       Ok(CString::new("hELLO").unwrap()));
+
+            assert_eq!(ptrace::read_cstring(child, at(string)),
+                       Ok(CString::new("string").unwrap()));
+            let pointers = ptrace::read_pointers(child, at(array)).unwrap();
+            assert_eq!(pointers, vec![at(string)]);
+
+            ptrace::cont(child, None).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        },
+    }
+
+    unsafe { munmap(page, len).unwrap() };
+}
+
+#[cfg(all(target_os = "linux", not(any(target_arch = "mips", target_arch = "mips64"))))]
+#[test]
+fn test_ptrace_interrupt_listen() {
+    use nix::sys::ptrace::Event;
+    use nix::sys::signal::{kill, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+
+    require_capability!(CAP_SYS_PTRACE);
+
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    match unsafe{fork()}.expect("Error: Fork Failed") {
+        Child => {
+            loop { unsafe { ::libc::pause(); } }
+        },
+
+        Parent { child } => {
+            let event_stop = Event::PTRACE_EVENT_STOP as i32;
+            ptrace::seize(child, Options::PTRACE_O_EXITKILL).unwrap();
+            ptrace::interrupt(child).unwrap();
+            assert_eq!(waitpid(child, None),
+                       Ok(WaitStatus::PtraceEvent(child, Signal::SIGTRAP, event_stop)));
+
+            kill(child, Signal::SIGUSR1).unwrap();
+            let pending = ptrace::peeksiginfo(child, 0, true).unwrap();
+            assert_eq!(pending.len(), 1);
+            assert_eq!(pending[0].si_signo, Signal::SIGUSR1.as_raw());
+            assert_eq!(ptrace::peeksiginfo(child, 1, true).unwrap().len(), 0);
+            assert_eq!(ptrace::peeksiginfo(child, 0, false).unwrap().len(), 0);
+
+            // Suppress SIGUSR1
+            ptrace::cont(child, None).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, Signal::SIGUSR1)));
+            ptrace::cont(child, None).unwrap();
+
+            // Let SIGSTOP stop the process, and wait for SIGCONT
+            kill(child, Signal::SIGSTOP).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, Signal::SIGSTOP)));
+            ptrace::cont(child, Signal::SIGSTOP).unwrap();
+            assert_eq!(waitpid(child, None),
+                       Ok(WaitStatus::PtraceGroupStop(child, Signal::SIGSTOP)));
+            ptrace::listen(child).unwrap();
+            kill(child, Signal::SIGCONT).unwrap();
+            assert_eq!(waitpid(child, None),
+                       Ok(WaitStatus::PtraceEvent(child, Signal::SIGTRAP, event_stop)));
+
+            kill(child, Signal::SIGKILL).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Signaled(child, Signal::SIGKILL, false)));
+        },
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn test_ptrace_seccomp_get_filter() {
+    use nix::sys::seccomp::{Action, Filter, FilterFlags};
+    use nix::sys::signal::{raise, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+
+    require_capability!(CAP_SYS_PTRACE, CAP_SYS_ADMIN);
+
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    // Allow everything
+    let filter = Filter::new(Action::Allow);
+
+    match unsafe{fork()}.expect("Error: Fork Failed") {
+        Child => {
+            if filter.install(FilterFlags::empty()).is_err() {
+                unsafe { ::libc::_exit(1) };
+            }
+            ptrace::traceme().unwrap();
+            raise(Signal::SIGSTOP).unwrap();
+            unsafe { ::libc::_exit(0); }
+        },
+
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, Signal::SIGSTOP)));
+
+            assert_eq!(ptrace::seccomp_get_filter(child, 0), filter.compile());
+            assert_eq!(ptrace::seccomp_get_filter(child, 1), Err(Errno::ENOENT));
+            let metadata = ptrace::seccomp_get_metadata(child, 0).unwrap();
+            assert_eq!(metadata.filter_off, 0);
+            assert_eq!(metadata.flags, 0);
+
+            ptrace::cont(child, None).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        },
+    }
+}
@@ -0,0 +1,248 @@
+use libc::{self, c_long};
+use nix::errno::Errno;
+use nix::fcntl::OFlag;
+use nix::poll::{poll, PollFd, PollFlags};
+use nix::sys::seccomp::*;
+use nix::sys::signal::Signal;
+use nix::sys::stat::fstat;
+use nix::sys::wait::{waitpid, WaitStatus};
+use nix::unistd::{close, fork, gettid, pipe, read, write, ForkResult::*};
+use std::os::unix::io::AsRawFd;
+use std::sync::mpsc;
+use std::thread;
+
+fn dup_errno(fd: u64) -> Errno {
+    let res = unsafe { libc::syscall(libc::SYS_dup, fd as c_long) };
+    assert_eq!(res, -1);
+    Errno::last()
+}
+
+#[test]
+fn test_filter_compile() {
+    // Three instructions check the architecture, and three more reject x32
+    // system calls on x86_64
+    let prologue = if cfg!(target_arch = "x86_64") { 6 } else { 3 };
+    assert_eq!(Filter::new(Action::Allow).compile().unwrap().len(), prologue + 1);
+    let filter = Filter::new(Action::Allow)
+        .rule_if(libc::SYS_dup, &[ArgCmp::new(6, CmpOp::Eq, 0)], Action::KillThread);
+    assert_eq!(filter.compile(), Err(Errno::EINVAL));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn test_filter_compile_x32() {
+    let program = Filter::new(Action::Allow)
+        .rule(libc::SYS_ptrace, Action::Errno(Errno::EPERM))
+        .compile()
+        .unwrap();
+    // Load the number, and kill the process if it has the x32 bit
+    assert_eq!(program[3], libc::sock_filter { code: 0x20, jt: 0, jf: 0, k: 0 });
+    assert_eq!(program[4], libc::sock_filter { code: 0x35, jt: 0, jf: 1, k: 0x4000_0000 });
+    assert_eq!(program[5], libc::sock_filter {
+        code: 0x06, jt: 0, jf: 0, k: libc::SECCOMP_RET_KILL_PROCESS
+    });
+}
+
+#[test]
+fn test_filter_args() {
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    match unsafe { fork() }.unwrap() {
+        Child => {
+            let res = set_no_new_privs().and_then(|_| {
+                Filter::new(Action::Allow)
+                    .rule_if(libc::SYS_dup, &[ArgCmp::new(0, CmpOp::Eq, 1000)],
+                             Action::Errno(Errno::EMLINK))
+                    .rule_if(libc::SYS_dup, &[ArgCmp::new(0, CmpOp::Ge, 2000),
+                                              ArgCmp::new(0, CmpOp::Lt, 2010)],
+                             Action::Errno(Errno::EXDEV))
+                    .rule_if(libc::SYS_dup, &[ArgCmp::new(0, CmpOp::Gt, 3990),
+                                              ArgCmp::new(0, CmpOp::Ne, 3999),
+                                              ArgCmp::new(0, CmpOp::Le, 4000)],
+                             Action::Errno(Errno::EDOM))
+                    .rule_if(libc::SYS_dup, &[ArgCmp::new(0, CmpOp::MaskedEq(0xff00), 0x3000)],
+                             Action::Errno(Errno::ENOTTY))
+                    .rule_if(libc::SYS_dup, &[ArgCmp::new(0, CmpOp::Gt, 1 << 40)],
+                             Action::Errno(Errno::ENOTBLK))
+                    .install(FilterFlags::empty())
+            });
+            let mut checks = vec![
+                (1000, Errno::EMLINK),
+                (1001, Errno::EBADF),
+                (2000, Errno::EXDEV),
+                (2009, Errno::EXDEV),
+                (2010, Errno::EBADF),
+                (3990, Errno::EBADF),
+                (3991, Errno::EDOM),
+                (3999, Errno::EBADF),
+                (4000, Errno::EDOM),
+                (4001, Errno::EBADF),
+                (0x30ff, Errno::ENOTTY),
+                (0x3100, Errno::EBADF),
+            ];
+            // Arguments are only 64-bit wide on 64-bit architectures
+            if cfg!(target_pointer_width = "64") {
+                checks.extend_from_slice(&[
+                    ((1 << 32) + 1000, Errno::EBADF),
+                    (1 << 40, Errno::EBADF),
+                    ((1 << 40) + 1, Errno::ENOTBLK),
+                ]);
+            }
+            let failed = checks.iter().position(|&(fd, errno)| dup_errno(fd) != errno);
+            let code = match (res, failed) {
+                (Err(_), _) => 100,
+                (Ok(_), Some(i)) => i as i32 + 1,
+                (Ok(_), None) => 0,
+            };
+            unsafe { libc::_exit(code) };
+        },
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        },
+    }
+}
+
+#[test]
+fn test_filter_trap() {
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    match unsafe { fork() }.unwrap() {
+        Child => {
+            let _ = set_no_new_privs().and_then(|_| {
+                Filter::new(Action::Allow)
+                    .rule(libc::SYS_getppid, Action::Trap(7))
+                    .install(FilterFlags::empty())
+            });
+            unsafe {
+                libc::syscall(libc::SYS_getppid);
+                libc::_exit(0);
+            }
+        },
+        Parent { child } => {
+            match waitpid(child, None) {
+                Ok(WaitStatus::Signaled(pid, Signal::SIGSYS, _)) => assert_eq!(pid, child),
+                status => panic!("unexpected status {:?}", status),
+            }
+        },
+    }
+}
+
+#[test]
+fn test_filter_tsync() {
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    match unsafe { fork() }.unwrap() {
+        Child => {
+            // A thread with a filter of its own can't be synchronized
+            let (tx, rx) = mpsc::channel();
+            let (done_tx, done_rx) = mpsc::channel::<()>();
+            let other = thread::spawn(move || {
+                let res = set_no_new_privs().and_then(|_| {
+                    Filter::new(Action::Allow)
+                        .rule(libc::SYS_getppid, Action::Errno(Errno::EPERM))
+                        .install(FilterFlags::empty())
+                });
+                let _ = tx.send(res.map(|_| gettid()));
+                let _ = done_rx.recv();
+            });
+            let filter = Filter::new(Action::Allow);
+            let ok = match (rx.recv(), filter.compile()) {
+                (Ok(Ok(tid)), Ok(program)) => {
+                    set_no_new_privs().is_ok()
+                    && filter.install(FilterFlags::SECCOMP_FILTER_FLAG_TSYNC).err()
+                        == Some(Errno::ESRCH)
+                    && set_mode_filter(&program, FilterFlags::SECCOMP_FILTER_FLAG_TSYNC)
+                        == Ok(tid.as_raw())
+                },
+                _ => false,
+            };
+            drop(done_tx);
+            let _ = other.join();
+            unsafe { libc::_exit(if ok { 0 } else { 1 }) };
+        },
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        },
+    }
+}
+
+#[test]
+fn test_mode_strict() {
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let (r, w) = pipe().unwrap();
+    match unsafe { fork() }.unwrap() {
+        Child => {
+            if set_mode_strict().is_ok() {
+                let _ = write(w, b"ok");
+                // Only exit(2) is allowed, not exit_group(2)
+                unsafe { libc::syscall(libc::SYS_exit, 0) };
+            }
+            unsafe { libc::_exit(1) };
+        },
+        Parent { child } => {
+            close(w).unwrap();
+            let mut buf = [0u8; 2];
+            assert_eq!(read(r, &mut buf), Ok(2));
+            assert_eq!(&buf, b"ok");
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+            close(r).unwrap();
+        },
+    }
+
+    match unsafe { fork() }.unwrap() {
+        Child => {
+            if set_mode_strict().is_ok() {
+                unsafe { libc::syscall(libc::SYS_getppid) };
+            }
+            unsafe { libc::_exit(1) };
+        },
+        Parent { child } => {
+            assert_eq!(waitpid(child, None),
+                       Ok(WaitStatus::Signaled(child, Signal::SIGKILL, false)));
+        },
+    }
+}
+
+// Filters only apply to the thread that installs them, so a separate thread
+// can be supervised without forking.
+#[test]
+fn test_user_notif() {
+    let (tx, rx) = mpsc::channel();
+    let target = thread::spawn(move || {
+        set_no_new_privs().unwrap();
+        let listener = Filter::new(Action::Allow)
+            .rule(libc::SYS_getppid, Action::UserNotif)
+            .rule_if(libc::SYS_dup, &[ArgCmp::new(0, CmpOp::Eq, 9999)], Action::UserNotif)
+            .install(FilterFlags::SECCOMP_FILTER_FLAG_NEW_LISTENER)
+            .unwrap()
+            .unwrap();
+        tx.send(listener).unwrap();
+        unsafe { (libc::syscall(libc::SYS_getppid), libc::syscall(libc::SYS_dup, 9999)) }
+    });
+    let listener = rx.recv().unwrap();
+
+    let mut fds = [PollFd::new(listener.as_raw_fd(), PollFlags::POLLIN)];
+    assert_eq!(poll(&mut fds, 5000), Ok(1));
+    let notif = listener.recv().unwrap();
+    assert_eq!(notif.syscall as c_long, libc::SYS_getppid);
+    assert!(listener.is_valid(notif.id).unwrap());
+    listener.send(notif.id, Response::Value(4242)).unwrap();
+    assert!(!listener.is_valid(notif.id).unwrap());
+
+    // Hand over a file descriptor, as if the target opened it
+    let notif = listener.recv().unwrap();
+    assert_eq!(notif.syscall as c_long, libc::SYS_dup);
+    assert_eq!(notif.args[0], 9999);
+    let (r, w) = pipe().unwrap();
+    let newfd = listener.add_fd(notif.id, r, None, OFlag::O_CLOEXEC).unwrap();
+    listener.send(notif.id, Response::Value(newfd as i64)).unwrap();
+
+    let (ppid, fd) = target.join().unwrap();
+    assert_eq!(ppid, 4242);
+    assert_eq!(fd, newfd as c_long);
+    assert_eq!(fstat(newfd).unwrap().st_ino, fstat(r).unwrap().st_ino);
+    for fd in &[newfd, r, w] {
+        close(*fd).unwrap();
+    }
+}
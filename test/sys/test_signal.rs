@@ -119,3 +119,189 @@ fn test_signal() {
     // Restore default signal handler
     unsafe { signal(Signal::SIGINT, SigHandler::SigDfl) }.unwrap();
 }
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn test_sigtimedwait() {
+    use nix::sys::time::{TimeSpec, TimeValLike};
+
+    let _m = crate::SIGNAL_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let mut mask = SigSet::empty();
+    mask.add(SIGUSR1);
+    mask.thread_block().unwrap();
+
+    // raise() sends the signal to the calling thread only
+    raise(SIGUSR1).unwrap();
+    let info = sigtimedwait(&mask, None).unwrap();
+    assert_eq!(info.signal(), SIGUSR1);
+    assert_eq!(info.origin(), SigOrigin::Kill { pid: getpid(), uid: getuid() });
+    assert_eq!(info.value(), None);
+
+    rt_tgsigqueueinfo(getpid(), gettid(), SIGUSR1, 0x1234).unwrap();
+    let info = sigtimedwait(&mask, Some(TimeSpec::seconds(1))).unwrap();
+    assert_eq!(info.signal(), SIGUSR1);
+    assert_eq!(info.pid(), Some(getpid()));
+    assert_eq!(info.value(), Some(0x1234));
+
+    assert_eq!(sigtimedwait(&mask, Some(TimeSpec::zero())).unwrap_err(),
+               Errno::EAGAIN);
+    mask.thread_unblock().unwrap();
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_sigqueue() {
+    use nix::sys::wait::{waitpid, WaitStatus};
+
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+    let _s = crate::SIGNAL_MTX.lock().expect("Mutex got poisoned by another test");
+
+    // Block the signal before forking, so that the child can't miss it
+    let mut mask = SigSet::empty();
+    mask.add(SIGUSR2);
+    let oldmask = mask.thread_swap_mask(SigmaskHow::SIG_BLOCK).unwrap();
+
+    match unsafe { fork() }.unwrap() {
+        ForkResult::Child => {
+            let ok = match sigwaitinfo(&mask) {
+                Ok(info) => info.signal() == SIGUSR2 &&
+                    info.origin() == SigOrigin::Queue {
+                        pid: getppid(),
+                        uid: getuid(),
+                        value: -7
+                    },
+                Err(_) => false,
+            };
+            unsafe { libc::_exit(if ok { 0 } else { 1 }) };
+        },
+        ForkResult::Parent { child } => {
+            oldmask.thread_set_mask().unwrap();
+            sigqueue(child, SIGUSR2, -7).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+}
+
+#[cfg(not(target_os = "redox"))]
+static ON_STACK: AtomicBool = AtomicBool::new(false);
+
+#[cfg(not(target_os = "redox"))]
+extern fn test_signal_stack_handler(_: libc::c_int) {
+    let on_stack = SignalStack::status()
+        .map(|flags| flags.contains(SigStackFlags::SS_ONSTACK)) == Ok(true);
+    ON_STACK.store(on_stack, Ordering::Relaxed);
+}
+
+#[test]
+#[cfg(not(target_os = "redox"))]
+fn test_signal_stack() {
+    let _m = crate::SIGNAL_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let stack = SignalStack::new(64 * 1024).unwrap();
+    assert!(stack.size() >= 64 * 1024);
+    assert_eq!(SignalStack::status().unwrap(), SigStackFlags::empty());
+
+    let action = SigAction::new(SigHandler::Handler(test_signal_stack_handler),
+                                SaFlags::SA_ONSTACK, SigSet::empty());
+    let old = unsafe { sigaction(SIGUSR2, &action) }.unwrap();
+    ON_STACK.store(false, Ordering::Relaxed);
+    raise(SIGUSR2).unwrap();
+    unsafe { sigaction(SIGUSR2, &old) }.unwrap();
+    assert!(ON_STACK.load(Ordering::Relaxed));
+
+    drop(stack);
+    assert!(!SignalStack::status().unwrap().contains(SigStackFlags::SS_ONSTACK));
+}
+
+#[test]
+#[cfg(not(target_os = "redox"))]
+fn test_signal_stack_drop_out_of_order() {
+    let _m = crate::SIGNAL_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let action = SigAction::new(SigHandler::Handler(test_signal_stack_handler),
+                                SaFlags::SA_ONSTACK, SigSet::empty());
+    let old = unsafe { sigaction(SIGUSR2, &action) }.unwrap();
+
+    let first = SignalStack::new(64 * 1024).unwrap();
+    let second = SignalStack::new(64 * 1024).unwrap();
+    // The second stack stays installed, and the first one is leaked
+    drop(first);
+    ON_STACK.store(false, Ordering::Relaxed);
+    raise(SIGUSR2).unwrap();
+    assert!(ON_STACK.load(Ordering::Relaxed));
+
+    // This reinstalls the first stack, which must still be usable
+    drop(second);
+    ON_STACK.store(false, Ordering::Relaxed);
+    raise(SIGUSR2).unwrap();
+    assert!(ON_STACK.load(Ordering::Relaxed));
+
+    unsafe { sigaction(SIGUSR2, &old) }.unwrap();
+    let mut disable = unsafe { std::mem::zeroed::<libc::stack_t>() };
+    disable.ss_flags = libc::SS_DISABLE;
+    assert_eq!(unsafe { libc::sigaltstack(&disable, std::ptr::null_mut()) }, 0);
+}
+
+#[test]
+#[cfg(not(target_os = "redox"))]
+fn test_signal_pipe() {
+    use nix::poll::{poll, PollFd, PollFlags};
+    use std::os::unix::io::AsRawFd;
+
+    let _m = crate::SIGNAL_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let mut set = SigSet::empty();
+    set.add(SIGUSR1);
+    set.add(SIGUSR2);
+    let mut usr1 = SigSet::empty();
+    usr1.add(SIGUSR1);
+
+    // The previous action is restored when the last pipe is dropped
+    let old = unsafe { signal(SIGUSR1, SigHandler::SigIgn) }.unwrap();
+    let mut pipe1 = SignalPipe::new(&set).unwrap();
+    let mut pipe2 = SignalPipe::new(&usr1).unwrap();
+    assert_eq!(pipe1.signals(), set);
+
+    raise(SIGUSR2).unwrap();
+    raise(SIGUSR1).unwrap();
+    let mut fds = [PollFd::new(pipe1.as_raw_fd(), PollFlags::POLLIN)];
+    assert_eq!(poll(&mut fds, 0), Ok(1));
+    assert_eq!(pipe1.read(), Ok(Some(SIGUSR2)));
+    assert_eq!(pipe1.read(), Ok(Some(SIGUSR1)));
+    assert_eq!(pipe1.read(), Ok(None));
+    assert_eq!(pipe2.read(), Ok(Some(SIGUSR1)));
+    assert_eq!(pipe2.read(), Ok(None));
+
+    drop(pipe1);
+    raise(SIGUSR1).unwrap();
+    assert_eq!(pipe2.read(), Ok(Some(SIGUSR1)));
+    drop(pipe2);
+    assert_eq!(unsafe { signal(SIGUSR1, old) }.unwrap(), SigHandler::SigIgn);
+
+    let mut segv = SigSet::empty();
+    segv.add(SIGSEGV);
+    assert_eq!(SignalPipe::new(&segv).unwrap_err(), Errno::EINVAL);
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn test_signal_pipe_siginfo() {
+    let _m = crate::SIGNAL_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let mut set = SigSet::empty();
+    set.add(SIGUSR2);
+    let mut pipe = SignalPipe::with_siginfo(&set).unwrap();
+
+    rt_tgsigqueueinfo(getpid(), gettid(), SIGUSR2, 99).unwrap();
+    let info = pipe.read_siginfo().unwrap().unwrap();
+    assert_eq!(info.signal(), SIGUSR2);
+    assert_eq!(info.origin(), SigOrigin::Queue { pid: getpid(), uid: getuid(), value: 99 });
+    assert_eq!(pipe.read_siginfo(), Ok(None));
+
+    raise(SIGUSR2).unwrap();
+    assert_eq!(pipe.read(), Ok(Some(SIGUSR2)));
+
+    let mut plain = SignalPipe::new(&set).unwrap();
+    assert_eq!(plain.read_siginfo().unwrap_err(), Errno::EINVAL);
+}
@@ -25,3 +25,63 @@ fn test_signalfd() {
     let signo = Signal::try_from(res.ssi_signo as i32).unwrap();
     assert_eq!(signo, signal::SIGUSR1);
 }
+
+#[test]
+fn test_signalfd_siginfo() {
+    use nix::sys::signalfd::SignalFd;
+    use nix::sys::signal::{self, SigInfo, SigOrigin, SigSet};
+    use nix::unistd::{getpid, getuid, gettid};
+
+    let _m = crate::SIGNAL_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let mut mask = SigSet::empty();
+    mask.add(signal::SIGUSR2);
+    mask.thread_block().unwrap();
+
+    let mut fd = SignalFd::new(&mask).unwrap();
+    signal::rt_tgsigqueueinfo(getpid(), gettid(), signal::SIGUSR2, 42).unwrap();
+
+    let res = fd.read_signal().unwrap().unwrap();
+    let info = SigInfo::try_from(&res).unwrap();
+    assert_eq!(info.signal(), signal::SIGUSR2);
+    assert_eq!(info.origin(),
+               SigOrigin::Queue { pid: getpid(), uid: getuid(), value: 42 });
+    mask.thread_unblock().unwrap();
+}
+
+#[test]
+fn test_signalfd_batch() {
+    use nix::sys::signalfd::{SfdFlags, SignalFd};
+    use nix::sys::signal::{self, SigSet};
+    use nix::unistd::{getpid, gettid};
+
+    let _m = crate::SIGNAL_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let mut mask = SigSet::empty();
+    mask.add(signal::SIGUSR1);
+    mask.add(signal::SIGUSR2);
+    let oldmask = SigSet::thread_get_mask().unwrap();
+    let mut fd = SignalFd::builder(&mask)
+        .flags(SfdFlags::SFD_NONBLOCK)
+        .thread_block()
+        .build()
+        .unwrap();
+    assert!(SigSet::thread_get_mask().unwrap().contains(signal::SIGUSR2));
+
+    signal::raise(signal::SIGUSR1).unwrap();
+    signal::raise(signal::SIGUSR2).unwrap();
+    let mut buf = Vec::with_capacity(8);
+    assert_eq!(fd.read_signals(&mut buf), Ok(2));
+    assert_eq!(buf.len(), 2);
+    assert_eq!(fd.read_signals(&mut buf), Ok(0));
+
+    signal::rt_tgsigqueueinfo(getpid(), gettid(), signal::SIGUSR1, 1).unwrap();
+    signal::rt_tgsigqueueinfo(getpid(), gettid(), signal::SIGUSR2, 2).unwrap();
+    let infos = fd.into_siginfos()
+        .map(|info| info.map(|info| (info.signal(), info.value())))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(infos, vec![(signal::SIGUSR1, Some(1)), (signal::SIGUSR2, Some(2))]);
+
+    oldmask.thread_set_mask().unwrap();
+}
@@ -101,7 +101,7 @@ pub fn test_abstract_sun_path_too_long() {
 pub fn test_addr_equality_abstract() {
     let name = String::from("nix\0abstract\0test");
     let addr1 = UnixAddr::new_abstract(name.as_bytes()).unwrap();
-    let mut addr2 = addr1.clone();
+    let mut addr2 = addr1;
 
     assert_eq!(addr1, addr2);
     assert_eq!(calculate_hash(&addr1), calculate_hash(&addr2));
@@ -1728,3 +1728,289 @@ fn test_recvmsg_rxq_ovfl() {
     nix::unistd::close(in_socket).unwrap();
     nix::unistd::close(out_socket).unwrap();
 }
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_msg_zerocopy() {
+    use nix::Error;
+    use nix::sys::socket::*;
+    use nix::sys::uio::IoVec;
+    use nix::unistd::{close, read};
+    use std::{thread, time};
+
+    let bits = MsgFlags::MSG_ZEROCOPY.bits();
+    assert_eq!(MsgFlags::from_bits(bits), Some(MsgFlags::MSG_ZEROCOPY));
+    assert_eq!(MsgFlags::from_bits_truncate(bits), MsgFlags::MSG_ZEROCOPY);
+    assert!(MsgFlags::all().contains(MsgFlags::MSG_ZEROCOPY));
+
+    let listener = socket(AddressFamily::Inet, SockType::Stream,
+                          SockFlag::empty(), None).unwrap();
+    let localhost = InetAddr::new(IpAddr::new_v4(127, 0, 0, 1), 0);
+    bind(listener, &SockAddr::new_inet(localhost)).unwrap();
+    listen(listener, 1).unwrap();
+    let address = getsockname(listener).unwrap();
+
+    let client = socket(AddressFamily::Inet, SockType::Stream,
+                        SockFlag::empty(), None).unwrap();
+    setsockopt(client, sockopt::ZeroCopy, &true).unwrap();
+    assert!(getsockopt(client, sockopt::ZeroCopy).unwrap());
+    connect(client, &address).unwrap();
+    let server = accept(listener).unwrap();
+
+    let message = [0x5au8; 4096];
+    assert_eq!(send(client, &message, MsgFlags::MSG_ZEROCOPY).unwrap(),
+               message.len());
+    let iov = [IoVec::from_slice(&message)];
+    assert_eq!(sendmsg(client, &iov, &[], MsgFlags::MSG_ZEROCOPY, None).unwrap(),
+               message.len());
+
+    let mut received = 0;
+    let mut buf = [0u8; 8192];
+    while received < 2 * message.len() {
+        received += read(server, &mut buf).unwrap();
+    }
+
+    // Loopback always copies, but the kernel still reports completion of
+    // both sends, possibly coalesced into a single range.
+    let mut completed = 0;
+    for _ in 0..100 {
+        let mut cmsgspace = nix::cmsg_space!(libc::sock_extended_err,
+                                             libc::sockaddr_in6);
+        let flags = MsgFlags::MSG_ERRQUEUE | MsgFlags::MSG_DONTWAIT;
+        match recvmsg(client, &[], Some(&mut cmsgspace), flags) {
+            Ok(r) => {
+                for cmsg in r.cmsgs() {
+                    match cmsg {
+                        ControlMessageOwned::ZeroCopyCompletion(c) => {
+                            assert_eq!(c.lo(), completed);
+                            assert!(c.copied());
+                            completed = c.hi() + 1;
+                        },
+                        _ => panic!("Unexpected control message"),
+                    }
+                }
+            },
+            Err(Error::EAGAIN) => (),
+            Err(e) => panic!("recvmsg: {}", e),
+        }
+        if completed == 2 {
+            break;
+        }
+        thread::sleep(time::Duration::from_millis(10));
+    }
+    assert_eq!(completed, 2);
+
+    close(server).unwrap();
+    close(client).unwrap();
+    close(listener).unwrap();
+}
+
+// Requires the vsock_loopback transport (Linux 5.6 and later).
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+pub fn test_vsock_loopback() {
+    use nix::sys::socket::*;
+    use nix::sys::time::{TimeVal, TimeValLike};
+    use nix::unistd::{close, write};
+
+    let listener = match socket(AddressFamily::Vsock, SockType::Stream,
+                                SockFlag::empty(), None) {
+        Ok(fd) => fd,
+        Err(_) => {
+            skip!("AF_VSOCK is not supported. Skipping test.");
+        }
+    };
+    bind(listener, &SockAddr::Vsock(VsockAddr::any(VsockAddr::PORT_ANY)))
+        .unwrap();
+    listen(listener, 1).unwrap();
+    let port = match getsockname(listener).unwrap() {
+        SockAddr::Vsock(addr) => {
+            assert_eq!(addr.cid(), VsockAddr::CID_ANY);
+            assert_ne!(addr.port(), VsockAddr::PORT_ANY);
+            addr.port()
+        },
+        addr => panic!("Unexpected address {}", addr),
+    };
+
+    let client = socket(AddressFamily::Vsock, SockType::Stream,
+                        SockFlag::empty(), None).unwrap();
+    let timeout = TimeVal::seconds(1);
+    setsockopt(client, sockopt::VsockConnectTimeout, &timeout).unwrap();
+    assert_eq!(getsockopt(client, sockopt::VsockConnectTimeout).unwrap(),
+               timeout);
+    if connect(client, &SockAddr::Vsock(VsockAddr::local(port))).is_err() {
+        close(client).unwrap();
+        close(listener).unwrap();
+        skip!("vsock_loopback is not available. Skipping test.");
+    }
+    let server = accept(listener).unwrap();
+
+    assert_eq!(getpeername(client).unwrap(),
+               SockAddr::Vsock(VsockAddr::local(port)));
+    match getpeername(server).unwrap() {
+        SockAddr::Vsock(addr) => assert_eq!(addr.cid(), VsockAddr::CID_LOCAL),
+        addr => panic!("Unexpected address {}", addr),
+    }
+
+    let size = getsockopt(server, sockopt::VsockBufferSize).unwrap();
+    let min = getsockopt(server, sockopt::VsockBufferMinSize).unwrap();
+    let max = getsockopt(server, sockopt::VsockBufferMaxSize).unwrap();
+    assert!(min <= size && size <= max);
+    setsockopt(server, sockopt::VsockBufferSize, &min).unwrap();
+    assert_eq!(getsockopt(server, sockopt::VsockBufferSize).unwrap(), min);
+
+    write(client, b"hello").unwrap();
+    let mut buf = [0u8; 5];
+    crate::read_exact(server, &mut buf);
+    assert_eq!(&buf, b"hello");
+
+    close(server).unwrap();
+    close(client).unwrap();
+    close(listener).unwrap();
+}
+
+#[test]
+pub fn test_unnamed_uds_addr() {
+    use nix::sys::socket::{socketpair, SockAddr, SockFlag, SockType, UnixAddrKind};
+
+    let addr = UnixAddr::new_unnamed();
+    assert_eq!(addr.kind(), UnixAddrKind::Unnamed);
+    assert_eq!(addr.path(), None);
+    assert_eq!(addr.to_string(), "<unbound UNIX socket>");
+
+    let (a, b) = socketpair(AddressFamily::Unix, SockType::Stream, None,
+                            SockFlag::empty()).unwrap();
+    match getsockname(a).unwrap() {
+        SockAddr::Unix(unix) => assert_eq!(unix, addr),
+        other => panic!("Unexpected address {}", other),
+    }
+    nix::unistd::close(a).unwrap();
+    nix::unistd::close(b).unwrap();
+}
+
+#[test]
+pub fn test_uds_addr_kind() {
+    use nix::sys::socket::{bind, socket, SockAddr, SockFlag, SockType,
+                           UnixAddrKind};
+
+    let tempdir = tempfile::tempdir().unwrap();
+    let sockname = tempdir.path().join("sock");
+    let addr = UnixAddr::new(&sockname).unwrap();
+    assert_eq!(addr.kind(), UnixAddrKind::Pathname(&sockname));
+
+    let sock = socket(AddressFamily::Unix, SockType::Stream, SockFlag::empty(),
+                      None).unwrap();
+    bind(sock, &SockAddr::Unix(addr)).unwrap();
+    // The bound address compares equal, even though the kernel reports the
+    // terminating null
+    assert_eq!(getsockname(sock).unwrap(), SockAddr::Unix(addr));
+    nix::unistd::close(sock).unwrap();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+pub fn test_abstract_uds_addr_display() {
+    use nix::sys::socket::{SockAddr, UnixAddrKind};
+
+    let name = b"nix\0abstract\xfftest";
+    let addr = UnixAddr::new_abstract(name).unwrap();
+    assert_eq!(addr.kind(), UnixAddrKind::Abstract(&name[..]));
+    assert_eq!(SockAddr::Unix(addr).to_str(), "@nix\\x00abstract\\xfftest");
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+pub fn test_uds_autobind() {
+    use nix::sys::socket::{bind, socket, SockAddr, SockFlag, SockType,
+                           UnixAddrKind};
+
+    let sock = socket(AddressFamily::Unix, SockType::Datagram,
+                      SockFlag::empty(), None).unwrap();
+    bind(sock, &SockAddr::Unix(UnixAddr::new_unnamed())).unwrap();
+    match getsockname(sock).unwrap() {
+        SockAddr::Unix(addr) => match addr.kind() {
+            // The kernel picks a name of five hex digits
+            UnixAddrKind::Abstract(name) => assert_eq!(name.len(), 5),
+            kind => panic!("Unexpected address kind {:?}", kind),
+        },
+        other => panic!("Unexpected address {}", other),
+    }
+    nix::unistd::close(sock).unwrap();
+}
+
+// Checkpoint a connected TCP socket in repair mode and restore it into a new
+// socket, which then carries on the connection.
+#[cfg(target_os = "linux")]
+#[test]
+pub fn test_tcp_repair() {
+    use nix::sys::socket::*;
+    use nix::unistd::{close, read, write};
+
+    require_capability!(CAP_NET_ADMIN);
+
+    let listener = socket(AddressFamily::Inet, SockType::Stream,
+                          SockFlag::empty(), None).unwrap();
+    let localhost = InetAddr::new(IpAddr::new_v4(127, 0, 0, 1), 0);
+    bind(listener, &SockAddr::new_inet(localhost)).unwrap();
+    listen(listener, 1).unwrap();
+    let address = getsockname(listener).unwrap();
+
+    let client = socket(AddressFamily::Inet, SockType::Stream,
+                        SockFlag::empty(), None).unwrap();
+    connect(client, &address).unwrap();
+    let server = accept(listener).unwrap();
+
+    // Leave some unread data in the client's receive queue
+    let pending = b"unread by the client";
+    write(server, pending).unwrap();
+    let mut buf = [0u8; 64];
+    while recv(client, &mut buf, MsgFlags::MSG_PEEK).unwrap() < pending.len() {}
+
+    setsockopt(client, sockopt::TcpRepair, &true).unwrap();
+    assert!(getsockopt(client, sockopt::TcpRepair).unwrap());
+    let recvq = tcp_repair_dump_queue(client, TcpRepairQueue::RecvQueue).unwrap();
+    assert_eq!(&recvq.data[..], &pending[..]);
+    assert_eq!(getsockopt(client, sockopt::TcpRepairQueue).unwrap(),
+               TcpRepairQueue::RecvQueue);
+    let sendq = tcp_repair_dump_queue(client, TcpRepairQueue::SendQueue).unwrap();
+    assert!(sendq.data.is_empty());
+    let window = getsockopt(client, sockopt::TcpRepairWindow).unwrap();
+    let local = getsockname(client).unwrap();
+    let peer = getpeername(client).unwrap();
+    // Closing a socket in repair mode doesn't notify the peer
+    close(client).unwrap();
+
+    let restored = socket(AddressFamily::Inet, SockType::Stream,
+                          SockFlag::empty(), None).unwrap();
+    setsockopt(restored, sockopt::TcpRepair, &true).unwrap();
+    tcp_repair_restore_queue_seq(restored, TcpRepairQueue::RecvQueue, &recvq).unwrap();
+    tcp_repair_restore_queue_seq(restored, TcpRepairQueue::SendQueue, &sendq).unwrap();
+    setsockopt(restored, sockopt::ReuseAddr, &true).unwrap();
+    bind(restored, &local).unwrap();
+    connect(restored, &peer).unwrap();
+    tcp_repair_restore_queue_data(restored, TcpRepairQueue::RecvQueue, &recvq).unwrap();
+    tcp_repair_restore_queue_data(restored, TcpRepairQueue::SendQueue, &sendq).unwrap();
+    setsockopt(restored, sockopt::TcpRepairOptions,
+               &vec![TcpRepairOpt::sack_permitted()]).unwrap();
+    setsockopt(restored, sockopt::TcpRepairWindow, &window).unwrap();
+    setsockopt(restored, sockopt::TcpRepairQueue, &TcpRepairQueue::NoQueue).unwrap();
+    setsockopt(restored, sockopt::TcpRepair, &false).unwrap();
+
+    let mut received = 0;
+    while received < pending.len() {
+        received += read(restored, &mut buf[received..]).unwrap();
+    }
+    assert_eq!(&buf[..received], &pending[..]);
+
+    let message = b"from the restored socket";
+    write(restored, message).unwrap();
+    let mut received = 0;
+    while received < message.len() {
+        received += read(server, &mut buf[received..]).unwrap();
+    }
+    assert_eq!(&buf[..received], &message[..]);
+
+    close(restored).unwrap();
+    close(server).unwrap();
+    close(listener).unwrap();
+}
@@ -0,0 +1,75 @@
+use nix::errno::Errno;
+use nix::sys::signal::{self, sigtimedwait, SigEvent, SigevNotify, SigOrigin, SigSet};
+use nix::sys::time::{TimeSpec, TimeValLike};
+use nix::sys::timer::{Expiration, Timer, TimerSetTimeFlags};
+use nix::time::{clock_gettime, ClockId};
+use nix::unistd::gettid;
+
+#[test]
+pub fn test_timer_get() {
+    let sigevent = SigEvent::new(SigevNotify::SigevNone);
+    let mut timer = Timer::new(ClockId::CLOCK_MONOTONIC, sigevent).unwrap();
+    assert_eq!(timer.get(), Ok(None));
+
+    timer
+        .set(
+            Expiration::IntervalDelayed(TimeSpec::seconds(60), TimeSpec::seconds(1)),
+            TimerSetTimeFlags::empty(),
+        )
+        .unwrap();
+    match timer.get().unwrap() {
+        Some(Expiration::IntervalDelayed(start, interval)) => {
+            assert!(start > TimeSpec::seconds(59));
+            assert_eq!(interval, TimeSpec::seconds(1));
+        }
+        e => panic!("unexpected expiration {:?}", e),
+    }
+    assert_eq!(timer.overruns(), Ok(0));
+}
+
+#[test]
+pub fn test_timer_thread_signal() {
+    let _m = crate::SIGNAL_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let mut mask = SigSet::empty();
+    mask.add(signal::SIGALRM);
+    mask.thread_block().unwrap();
+
+    let sigevent = SigEvent::new(SigevNotify::SigevThreadId {
+        signal: signal::SIGALRM,
+        thread_id: gettid().as_raw(),
+        si_value: 42,
+    });
+    let mut timer = Timer::new(ClockId::CLOCK_MONOTONIC, sigevent).unwrap();
+    let now = clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap();
+    timer
+        .set(
+            Expiration::OneShot(now + TimeSpec::milliseconds(10)),
+            TimerSetTimeFlags::TIMER_ABSTIME,
+        )
+        .unwrap();
+
+    let info = sigtimedwait(&mask, Some(TimeSpec::seconds(5))).unwrap();
+    assert_eq!(info.signal(), signal::SIGALRM);
+    match info.origin() {
+        SigOrigin::Timer { overrun, value, .. } => {
+            assert_eq!(overrun, 0);
+            assert_eq!(value, 42);
+        }
+        o => panic!("unexpected origin {:?}", o),
+    }
+    assert_eq!(timer.get(), Ok(None));
+
+    timer
+        .set(Expiration::Interval(TimeSpec::seconds(60)), TimerSetTimeFlags::empty())
+        .unwrap();
+    assert!(timer.get().unwrap().is_some());
+    timer
+        .set(Expiration::OneShot(TimeSpec::zero()), TimerSetTimeFlags::empty())
+        .unwrap();
+    assert_eq!(timer.get(), Ok(None));
+    drop(timer);
+
+    assert_eq!(sigtimedwait(&mask, Some(TimeSpec::zero())).unwrap_err(), Errno::EAGAIN);
+    mask.thread_unblock().unwrap();
+}
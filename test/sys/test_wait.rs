@@ -44,6 +44,18 @@ fn test_waitstatus_from_raw() {
     assert_eq!(WaitStatus::from_raw(pid, 0x7f7f), Err(Errno::EINVAL));
 }
 
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn test_waitstatus_from_raw_reserved_rt_signal() {
+    use std::convert::TryFrom;
+
+    // Stopped by a real-time signal that the C library reserves
+    let pid = Pid::from_raw(1);
+    let signal = Signal::try_from(33).unwrap();
+    assert_eq!(WaitStatus::from_raw(pid, (33 << 8) | 0x7f),
+               Ok(WaitStatus::Stopped(pid, signal)));
+}
+
 #[test]
 fn test_waitstatus_pid() {
     let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
@@ -7,6 +7,8 @@ extern crate lazy_static;
 
 mod common;
 mod sys;
+#[cfg(target_os = "linux")]
+mod test_cgroup;
 #[cfg(not(target_os = "redox"))]
 mod test_dir;
 mod test_fcntl;
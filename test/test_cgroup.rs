@@ -0,0 +1,103 @@
+use nix::cgroup::*;
+use nix::errno::Errno;
+use nix::sys::stat::Mode;
+use nix::unistd::Pid;
+use std::fs;
+use std::os::unix::io::AsRawFd;
+use tempfile::tempdir;
+
+// Populate a directory with the interface files of a cgroup, as a stand-in
+// for the real hierarchy, which tests can't rely on being mounted or
+// writable.
+fn fake_cgroup(dir: &std::path::Path) {
+    let files = [
+        ("cgroup.procs", ""),
+        ("cgroup.controllers", "cpu memory pids\n"),
+        ("cgroup.subtree_control", ""),
+        ("memory.max", "max\n"),
+        ("memory.current", "8192\n"),
+        ("memory.events", "low 0\nhigh 3\nmax 2\noom 1\noom_kill 1\noom_group_kill 0\n"),
+        ("pids.max", "max\n"),
+        ("cpu.max", "max 100000\n"),
+        ("cpu.stat", "usage_usec 1500\nuser_usec 1000\nsystem_usec 500\n\
+                      nr_periods 4\nnr_throttled 1\nthrottled_usec 250\n"),
+    ];
+    for &(name, contents) in files.iter() {
+        fs::write(dir.join(name), contents).unwrap();
+    }
+}
+
+#[test]
+fn test_cgroup_files() {
+    let tmp = tempdir().unwrap();
+    fake_cgroup(tmp.path());
+    let cgroup = Cgroup::open(tmp.path()).unwrap();
+    assert!(cgroup.as_raw_fd() >= 0);
+
+    assert_eq!(cgroup.controllers().unwrap(), vec!["cpu", "memory", "pids"]);
+    cgroup.set_subtree_control(&["memory", "pids"], &["cpu"]).unwrap();
+    assert_eq!(cgroup.read("cgroup.subtree_control").unwrap(), "+memory +pids -cpu");
+
+    assert_eq!(cgroup.memory_max().unwrap(), Limit::Max);
+    cgroup.set_memory_max(Limit::Value(1 << 20)).unwrap();
+    assert_eq!(cgroup.memory_max().unwrap(), Limit::Value(1 << 20));
+    cgroup.set_memory_max(Limit::Max).unwrap();
+    assert_eq!(cgroup.memory_max().unwrap(), Limit::Max);
+    assert_eq!(cgroup.memory_current().unwrap(), 8192);
+
+    cgroup.set_pids_max(Limit::Value(16)).unwrap();
+    assert_eq!(cgroup.pids_max().unwrap(), Limit::Value(16));
+
+    assert_eq!(cgroup.cpu_max().unwrap(), CpuMax { quota: Limit::Max, period: 100000 });
+    let max = CpuMax { quota: Limit::Value(50000), period: 100000 };
+    cgroup.set_cpu_max(max).unwrap();
+    assert_eq!(cgroup.read("cpu.max").unwrap(), "50000 100000");
+    assert_eq!(cgroup.cpu_max().unwrap(), max);
+
+    let events = cgroup.memory_events().unwrap();
+    assert_eq!(events, MemoryEvents { low: 0, high: 3, max: 2, oom: 1, oom_kill: 1 });
+    let stat = cgroup.cpu_stat().unwrap();
+    assert_eq!(stat.usage_usec, 1500);
+    assert_eq!(stat.nr_throttled, 1);
+    assert_eq!(stat.throttled_usec, 250);
+
+    assert_eq!(cgroup.read_value::<_, Limit>("cgroup.controllers"), Err(Errno::EINVAL));
+    assert_eq!(cgroup.read("missing"), Err(Errno::ENOENT));
+}
+
+#[test]
+fn test_cgroup_children() {
+    let tmp = tempdir().unwrap();
+    let root = Cgroup::open(tmp.path()).unwrap();
+
+    let child = root.create_child("job", Mode::from_bits_truncate(0o755)).unwrap();
+    assert_eq!(root.create_child("job", Mode::from_bits_truncate(0o755)),
+               Err(Errno::EEXIST));
+    fake_cgroup(&tmp.path().join("job"));
+
+    child.add_process(Pid::from_raw(42)).unwrap();
+    assert_eq!(child.processes().unwrap(), vec![Pid::from_raw(42)]);
+    assert_eq!(root.open_child("job").unwrap().processes().unwrap(),
+               vec![Pid::from_raw(42)]);
+
+    // Unlike the real hierarchy, the fake one needs to be emptied first
+    for entry in fs::read_dir(tmp.path().join("job")).unwrap() {
+        fs::remove_file(entry.unwrap().path()).unwrap();
+    }
+    drop(child);
+    root.remove_child("job").unwrap();
+    assert_eq!(root.open_child("job"), Err(Errno::ENOENT));
+}
+
+#[test]
+fn test_cgroup_values() {
+    assert_eq!("max\n".parse::<Limit>().unwrap(), Limit::Max);
+    assert_eq!("1024".parse::<Limit>().unwrap(), Limit::Value(1024));
+    assert!("-1".parse::<Limit>().is_err());
+    assert!("max".parse::<CpuMax>().is_err());
+    assert!("max 100000 1".parse::<CpuMax>().is_err());
+    assert!("oom".parse::<MemoryEvents>().is_err());
+    // Unknown keys are ignored
+    assert_eq!("usage_usec 1\ncore_sched.force_idle_usec 0".parse::<CpuStat>().unwrap(),
+               CpuStat { usage_usec: 1, ..CpuStat::default() });
+}
@@ -11,15 +11,22 @@ mod test_mount {
     use std::io::{self, Read, Write};
     use std::os::unix::fs::OpenOptionsExt;
     use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::io::AsRawFd;
     use std::process::{self, Command};
 
     use libc::{EACCES, EROFS};
 
     use nix::errno::Errno;
-    use nix::mount::{mount, umount, MsFlags};
+    use nix::fcntl::AtFlags;
+    use nix::mount::{fsconfig_create, fsconfig_set_string, fsmount, fsopen, mount,
+                     mount_setattr, move_mount, open_tree, umount, FsmountFlags,
+                     FsopenFlags, MountAttr, MountInfo, MountSetattr, MoveMountFlags,
+                     MsFlags, OpenTreeFlags};
     use nix::sched::{unshare, CloneFlags};
     use nix::sys::stat::{self, Mode};
-    use nix::unistd::getuid;
+    use nix::sys::statfs::{statfs, TMPFS_MAGIC};
+    use nix::sys::statvfs::{statvfs, FsFlags};
+    use nix::unistd::{getuid, Pid};
 
     use tempfile;
 
@@ -175,6 +182,95 @@ exit 23";
         assert_eq!(buf, SCRIPT_CONTENTS);
     }
 
+    pub fn test_fsmount_tmpfs() {
+        let tempdir = tempfile::tempdir().unwrap();
+
+        let fs = fsopen("tmpfs", FsopenFlags::FSOPEN_CLOEXEC)
+            .unwrap_or_else(|e| panic!("fsopen failed: {}", e));
+        // Errors are reported for each parameter
+        assert_eq!(fsconfig_set_string(&fs, "no_such_option", "1"),
+                   Err(Errno::EINVAL));
+        fsconfig_set_string(&fs, "size", "1m")
+            .unwrap_or_else(|e| panic!("fsconfig failed: {}", e));
+        fsconfig_create(&fs).unwrap_or_else(|e| panic!("fsconfig failed: {}", e));
+        let mnt = fsmount(&fs, FsmountFlags::FSMOUNT_CLOEXEC, MountAttr::MOUNT_ATTR_NODEV)
+            .unwrap_or_else(|e| panic!("fsmount failed: {}", e));
+        move_mount(Some(mnt.as_raw_fd()), "", None, tempdir.path(),
+                   MoveMountFlags::MOVE_MOUNT_F_EMPTY_PATH)
+            .unwrap_or_else(|e| panic!("move_mount failed: {}", e));
+        drop(mnt);
+
+        assert_eq!(statfs(tempdir.path()).unwrap().filesystem_type(), TMPFS_MAGIC);
+        let flags = statvfs(tempdir.path()).unwrap().flags();
+        assert!(flags.contains(FsFlags::ST_NODEV));
+        assert!(!flags.contains(FsFlags::ST_RDONLY));
+
+        let attr = MountSetattr {
+            set: MountAttr::MOUNT_ATTR_RDONLY,
+            ..Default::default()
+        };
+        mount_setattr(None, tempdir.path(), AtFlags::empty(), &attr)
+            .unwrap_or_else(|e| panic!("mount_setattr failed: {}", e));
+        let flags = statvfs(tempdir.path()).unwrap().flags();
+        assert!(flags.contains(FsFlags::ST_RDONLY));
+
+        umount(tempdir.path()).unwrap_or_else(|e| panic!("umount failed: {}", e));
+    }
+
+    pub fn test_open_tree_clone() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let mount_point = tempfile::tempdir().unwrap();
+
+        mount(NONE,
+              tempdir.path(),
+              Some(b"tmpfs".as_ref()),
+              MsFlags::empty(),
+              NONE)
+            .unwrap_or_else(|e| panic!("mount failed: {}", e));
+        let tree = open_tree(None, tempdir.path(),
+                             OpenTreeFlags::OPEN_TREE_CLONE | OpenTreeFlags::OPEN_TREE_CLOEXEC)
+            .unwrap_or_else(|e| panic!("open_tree failed: {}", e));
+        move_mount(Some(tree.as_raw_fd()), "", None, mount_point.path(),
+                   MoveMountFlags::MOVE_MOUNT_F_EMPTY_PATH)
+            .unwrap_or_else(|e| panic!("move_mount failed: {}", e));
+        drop(tree);
+
+        assert_eq!(statfs(mount_point.path()).unwrap().filesystem_type(), TMPFS_MAGIC);
+
+        umount(mount_point.path()).unwrap_or_else(|e| panic!("umount failed: {}", e));
+        umount(tempdir.path()).unwrap_or_else(|e| panic!("umount failed: {}", e));
+    }
+
+    pub fn test_mountinfo() {
+        let tempdir = tempfile::tempdir().unwrap();
+        // The kernel escapes spaces in mountinfo
+        let mount_point = tempdir.path().join("mount point");
+        fs::create_dir(&mount_point).unwrap();
+
+        mount(NONE,
+              &mount_point,
+              Some(b"tmpfs".as_ref()),
+              MsFlags::MS_RDONLY | MsFlags::MS_NOEXEC,
+              Some(b"size=1m".as_ref()))
+            .unwrap_or_else(|e| panic!("mount failed: {}", e));
+
+        let entry = MountInfo::new()
+            .unwrap()
+            .map(|entry| entry.unwrap())
+            .find(|entry| entry.mount_point == mount_point)
+            .expect("mount not found in mountinfo");
+        assert_eq!(entry.fs_type, "tmpfs");
+        assert!(entry.flags.contains(MsFlags::MS_RDONLY | MsFlags::MS_NOEXEC));
+        assert!(!entry.flags.contains(MsFlags::MS_NOSUID));
+        assert!(entry.super_options.iter().any(|option| option == "size=1024k"));
+        assert_eq!(entry.dev, stat::stat(&mount_point).unwrap().st_dev);
+
+        umount(&mount_point).unwrap_or_else(|e| panic!("umount failed: {}", e));
+        assert!(!MountInfo::for_pid(Pid::this())
+            .unwrap()
+            .any(|entry| entry.unwrap().mount_point == mount_point));
+    }
+
     pub fn setup_namespaces() {
         // Hold on to the uid in the parent namespace.
         let uid = getuid();
@@ -223,14 +319,18 @@ macro_rules! run_tests {
 fn main() {
     use test_mount::{setup_namespaces, test_mount_tmpfs_without_flags_allows_rwx,
                      test_mount_rdonly_disallows_write, test_mount_noexec_disallows_exec,
-                     test_mount_bind};
+                     test_mount_bind, test_fsmount_tmpfs, test_open_tree_clone,
+                     test_mountinfo};
     skip_if_cirrus!("Fails for an unknown reason Cirrus CI.  Bug #1351");
     setup_namespaces();
 
     run_tests!(test_mount_tmpfs_without_flags_allows_rwx,
                test_mount_rdonly_disallows_write,
                test_mount_noexec_disallows_exec,
-               test_mount_bind);
+               test_mount_bind,
+               test_fsmount_tmpfs,
+               test_open_tree_clone,
+               test_mountinfo);
 }
 
 #[cfg(not(target_os = "linux"))]
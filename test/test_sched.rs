@@ -1,5 +1,7 @@
 use nix::sched::{sched_getaffinity, sched_setaffinity, CpuSet};
 use nix::unistd::Pid;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use crate::*;
 
 #[test]
 fn test_sched_affinity() {
@@ -30,3 +32,365 @@ fn test_sched_affinity() {
     // Finally, reset the initial CPU set
     sched_setaffinity(Pid::from_raw(0), &initial_affinity).unwrap();
 }
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_clone3() {
+    use nix::sched::{clone3, CloneArgs};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::{close, ForkResult};
+    use std::os::unix::io::RawFd;
+
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let mut pidfd: RawFd = -1;
+    let args = CloneArgs::new().pidfd(&mut pidfd).clear_sighand();
+    match unsafe { clone3(args) } {
+        Ok(ForkResult::Child) => unsafe { libc::_exit(7) },
+        Ok(ForkResult::Parent { child }) => {
+            assert!(pidfd >= 0);
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 7)));
+            close(pidfd).unwrap();
+        },
+        // clone3 is only available since Linux 5.3
+        Err(nix::Error::ENOSYS) => {
+            skip!("clone3 is not supported. Skipping test.");
+        },
+        Err(e) => panic!("clone3 failed: {}", e),
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_clone3_flags() {
+    use nix::sched::{clone3, CloneArgs, CloneFlags};
+    use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+    use nix::unistd::ForkResult;
+
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    // CLONE_IO is the sign bit of the flags
+    let args = CloneArgs::new().flags(CloneFlags::CLONE_IO).exit_signal(None);
+    match unsafe { clone3(args) } {
+        Ok(ForkResult::Child) => unsafe { libc::_exit(7) },
+        Ok(ForkResult::Parent { child }) => {
+            // Without an exit signal, only __WALL or __WCLONE waits for it
+            assert_eq!(waitpid(child, Some(WaitPidFlag::WNOHANG)),
+                       Err(nix::Error::ECHILD));
+            assert_eq!(waitpid(child, Some(WaitPidFlag::__WALL)),
+                       Ok(WaitStatus::Exited(child, 7)));
+        },
+        Err(nix::Error::ENOSYS) => {
+            skip!("clone3 is not supported. Skipping test.");
+        },
+        Err(e) => panic!("clone3 failed: {}", e),
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_clone3_set_tid() {
+    use nix::sched::{clone3, CloneArgs, CloneFlags};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::ForkResult;
+    use std::fs;
+    use std::path::Path;
+
+    require_capability!(CAP_SYS_ADMIN);
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let pid_max: i32 = fs::read_to_string("/proc/sys/kernel/pid_max").unwrap()
+        .trim().parse().unwrap();
+    let outer = (1..pid_max).rev()
+        .find(|pid| !Path::new(&format!("/proc/{}", pid)).exists())
+        .map(Pid::from_raw)
+        .unwrap();
+
+    let set_tid = [Pid::from_raw(1), outer];
+    let args = CloneArgs::new().flags(CloneFlags::CLONE_NEWPID).set_tid(&set_tid);
+    match unsafe { clone3(args) } {
+        Ok(ForkResult::Child) => unsafe {
+            let pid = libc::syscall(libc::SYS_getpid);
+            libc::_exit(if pid == 1 { 0 } else { 1 })
+        },
+        Ok(ForkResult::Parent { child }) => {
+            assert_eq!(child, outer);
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        },
+        // set_tid is only available since Linux 5.5
+        Err(nix::Error::ENOSYS) | Err(nix::Error::E2BIG) => {
+            skip!("clone3 with set_tid is not supported. Skipping test.");
+        },
+        Err(e) => panic!("clone3 failed: {}", e),
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn test_namespace_fd() {
+    use nix::sched::Namespace;
+    use nix::sys::stat::stat;
+
+    let uts = Namespace::Uts.open(Pid::this()).unwrap();
+    assert_eq!(uts.kind().unwrap(), Namespace::Uts);
+    assert_eq!(uts.inode().unwrap(),
+               stat("/proc/self/ns/uts").unwrap().st_ino);
+    let again = Namespace::Uts.open(Pid::this()).unwrap();
+    assert!(uts.is_same(&again).unwrap());
+    let net = Namespace::Net.open(Pid::this()).unwrap();
+    assert!(!uts.is_same(&net).unwrap());
+
+    let userns = uts.user_ns().unwrap();
+    assert_eq!(userns.kind().unwrap(), Namespace::User);
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn test_namespace_kind() {
+    use nix::errno::Errno;
+    use nix::sched::Namespace;
+
+    let mut namespaces = vec![Namespace::Cgroup, Namespace::Ipc, Namespace::Mnt,
+                              Namespace::Net, Namespace::Pid, Namespace::User,
+                              Namespace::Uts];
+    #[cfg(target_os = "linux")]
+    namespaces.push(Namespace::Time);
+    for ns in namespaces {
+        // Older kernels lack some kinds of namespaces
+        let fd = match ns.open(Pid::this()) {
+            Ok(fd) => fd,
+            Err(Errno::ENOENT) => continue,
+            Err(e) => panic!("opening {} failed: {}", ns.proc_name(), e),
+        };
+        assert_eq!(fd.kind().unwrap(), ns);
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_setns_pidfd() {
+    use nix::sched::{setns_pidfd, unshare, CloneFlags, Namespace};
+    use nix::sys::signal::{kill, Signal};
+    use nix::sys::wait::waitpid;
+    use nix::unistd::{close, fork, gethostname, pause, pipe, read, sethostname, write,
+                      ForkResult};
+    use std::thread;
+
+    require_capability!(CAP_SYS_ADMIN);
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let (r, w) = pipe().unwrap();
+    let child = match unsafe { fork() }.unwrap() {
+        ForkResult::Child => {
+            unshare(CloneFlags::CLONE_NEWUTS).unwrap();
+            sethostname("nix-setns-test").unwrap();
+            write(w, b"1").unwrap();
+            loop {
+                pause();
+            }
+        },
+        ForkResult::Parent { child } => child,
+    };
+    close(w).unwrap();
+    let mut buf = [0u8; 1];
+    assert_eq!(read(r, &mut buf), Ok(1));
+    close(r).unwrap();
+
+    let res = unsafe { libc::syscall(libc::SYS_pidfd_open, child.as_raw(), 0) };
+    // Enter the namespaces from another thread, to leave this one untouched
+    let hostname = if res >= 0 {
+        let pidfd = res as libc::c_int;
+        let hostname = thread::spawn(move || {
+            setns_pidfd(pidfd, &[Namespace::Uts]).map(|_| {
+                let mut buf = [0u8; 64];
+                gethostname(&mut buf).unwrap().to_str().unwrap().to_owned()
+            })
+        }).join().unwrap();
+        close(pidfd).unwrap();
+        Some(hostname)
+    } else {
+        None
+    };
+
+    kill(child, Signal::SIGKILL).unwrap();
+    waitpid(child, None).unwrap();
+
+    match hostname {
+        Some(Ok(hostname)) => assert_eq!(hostname, "nix-setns-test"),
+        // pidfd_open and setns with a pidfd are only available since Linux
+        // 5.3 and 5.8
+        None | Some(Err(nix::Error::EINVAL)) => {
+            skip!("setns with a pidfd is not supported. Skipping test.");
+        },
+        Some(Err(e)) => panic!("setns_pidfd failed: {}", e),
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn test_id_map_validation() {
+    use nix::sched::{set_id_maps, GidMap, UidMap};
+    use nix::unistd::{Gid, Uid};
+
+    let uid = |id| Uid::from_raw(id);
+    let gid = GidMap::new(Gid::from_raw(0), Gid::from_raw(1000), 1);
+    // Overlapping inside the namespace
+    let overlapping = [UidMap::new(uid(0), uid(1000), 10),
+                       UidMap::new(uid(5), uid(2000), 10)];
+    assert_eq!(set_id_maps(Pid::this(), &overlapping, &[gid]), Err(nix::Error::EINVAL));
+    // Overlapping in the parent namespace
+    let overlapping = [UidMap::new(uid(0), uid(1000), 10),
+                       UidMap::new(uid(100), uid(1009), 1)];
+    assert_eq!(set_id_maps(Pid::this(), &overlapping, &[gid]), Err(nix::Error::EINVAL));
+    // Empty ranges and ranges past the last ID
+    assert_eq!(set_id_maps(Pid::this(), &[UidMap::new(uid(0), uid(1000), 0)], &[gid]),
+               Err(nix::Error::EINVAL));
+    assert_eq!(set_id_maps(Pid::this(), &[UidMap::new(uid(u32::MAX), uid(0), 2)], &[gid]),
+               Err(nix::Error::EINVAL));
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn test_set_id_maps() {
+    use nix::sched::{read_gid_map, read_uid_map, set_id_maps, unshare, CloneFlags,
+                     GidMap, UidMap};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::{close, fork, getgid, getuid, pipe, read, write, ForkResult, Gid,
+                      Uid};
+
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let (uid, gid) = (getuid(), getgid());
+    let (ready_r, ready_w) = pipe().unwrap();
+    let (go_r, go_w) = pipe().unwrap();
+    match unsafe { fork() }.unwrap() {
+        ForkResult::Child => {
+            let mut buf = [0u8; 1];
+            let status = if unshare(CloneFlags::CLONE_NEWUSER).is_err() {
+                write(ready_w, b"n").unwrap();
+                0
+            } else {
+                write(ready_w, b"y").unwrap();
+                read(go_r, &mut buf).unwrap();
+                if getuid().is_root() && getgid() == Gid::from_raw(0) { 0 } else { 1 }
+            };
+            unsafe { libc::_exit(status) };
+        },
+        ForkResult::Parent { child } => {
+            close(ready_w).unwrap();
+            close(go_r).unwrap();
+            let mut buf = [0u8; 1];
+            read(ready_r, &mut buf).unwrap();
+            if &buf == b"y" {
+                let uid_map = [UidMap::new(Uid::from_raw(0), uid, 1)];
+                let gid_map = [GidMap::new(Gid::from_raw(0), gid, 1)];
+                set_id_maps(child, &uid_map, &gid_map).unwrap();
+                assert_eq!(read_uid_map(child).unwrap(), uid_map);
+                assert_eq!(read_gid_map(child).unwrap(), gid_map);
+            }
+            write(go_w, b"1").unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+            close(ready_r).unwrap();
+            close(go_w).unwrap();
+            if &buf != b"y" {
+                skip!("User namespaces are not available. Skipping test.");
+            }
+        },
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn test_sched_policy() {
+    use nix::sched::*;
+
+    assert_eq!(sched_getscheduler(Pid::from_raw(0)).unwrap(), SchedPolicy::SCHED_OTHER);
+    assert!(sched_get_priority_min(SchedPolicy::SCHED_FIFO).unwrap() <
+            sched_get_priority_max(SchedPolicy::SCHED_FIFO).unwrap());
+    assert_eq!(sched_get_priority_max(SchedPolicy::SCHED_OTHER).unwrap(), 0);
+    sched_rr_get_interval(Pid::from_raw(0)).unwrap();
+}
+
+// Change the scheduling of a separate thread, so that other tests aren't
+// affected.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn test_sched_setattr() {
+    use nix::sched::*;
+    use nix::unistd::gettid;
+    use std::thread;
+
+    thread::spawn(|| {
+        let this = Pid::from_raw(0);
+        let initial = sched_getattr(this).unwrap();
+        assert_eq!(initial.policy, SchedPolicy::SCHED_OTHER);
+
+        // Lowering the priority is always allowed
+        let mut attr = SchedAttr::new(SchedPolicy::SCHED_BATCH);
+        attr.nice = (initial.nice + 1).min(19);
+        sched_setattr(this, &attr).unwrap();
+        assert_eq!(sched_getscheduler(this).unwrap(), SchedPolicy::SCHED_BATCH);
+        let current = sched_getattr(this).unwrap();
+        assert_eq!(current.policy, SchedPolicy::SCHED_BATCH);
+        assert_eq!(current.nice, attr.nice);
+
+        // Nice values of individual threads can be read with their thread ID
+        let tid = PriorityTarget::Process(gettid());
+        assert_eq!(getpriority(tid).unwrap(), attr.nice);
+        setpriority(tid, 19).unwrap();
+        assert_eq!(getpriority(tid).unwrap(), 19);
+        assert_eq!(sched_getattr(this).unwrap().nice, 19);
+
+        sched_setscheduler(this, SchedPolicy::SCHED_IDLE, 0).unwrap();
+        assert_eq!(sched_getscheduler(this).unwrap(), SchedPolicy::SCHED_IDLE);
+    }).join().unwrap();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn test_cpu_mask() {
+    use nix::sched::CpuMask;
+
+    let mask: CpuMask = "0-3,8,10-11\n".parse().unwrap();
+    assert_eq!(mask.iter().collect::<Vec<_>>(), vec![0, 1, 2, 3, 8, 10, 11]);
+    assert_eq!(mask.to_string(), "0-3,8,10-11");
+    assert!("".parse::<CpuMask>().unwrap().is_empty());
+    assert!("3-1".parse::<CpuMask>().is_err());
+    assert!("1,x".parse::<CpuMask>().is_err());
+    assert!("8191".parse::<CpuMask>().unwrap().is_set(8191));
+    assert!("0-18446744073709551615".parse::<CpuMask>().is_err());
+
+    // Not limited to CPU_SETSIZE
+    let other: CpuMask = [3, 4, 2000].iter().cloned().collect();
+    assert!(other.is_set(2000));
+    assert_eq!(mask.union(&other).to_string(), "0-4,8,10-11,2000");
+    assert_eq!(mask.intersection(&other).to_string(), "3");
+    assert_eq!(mask.intersection(&other), [3].iter().cloned().collect());
+
+    let mut cpuset = CpuSet::new();
+    cpuset.set(1).unwrap();
+    cpuset.set(5).unwrap();
+    assert_eq!(CpuMask::from(cpuset).to_string(), "1,5");
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn test_sched_affinity_mask() {
+    use nix::sched::*;
+
+    let initial = sched_getaffinity_mask(Pid::from_raw(0)).unwrap();
+    assert!(initial.count() > 0);
+    let cpu = sched_getcpu().unwrap();
+    assert!(initial.is_set(cpu));
+    assert!(initial.is_set(getcpu().unwrap().0));
+
+    // Pin to the current CPU in a separate thread, so that other tests
+    // aren't affected.
+    std::thread::spawn(move || {
+        let mut mask = CpuMask::new();
+        mask.set(cpu);
+        sched_setaffinity_mask(Pid::from_raw(0), &mask).unwrap();
+        assert_eq!(sched_getaffinity_mask(Pid::from_raw(0)).unwrap(), mask);
+        assert_eq!(sched_getcpu().unwrap(), cpu);
+    }).join().unwrap();
+}